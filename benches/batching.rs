@@ -0,0 +1,93 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Draws the same dense mesh with batching, which merges it into one path, and without batching,
+//! which draws one path per line. The chart is drawn into a frame of wgpu, which tessellates the
+//! paths when they are stroked and needs no device, so the geometry cost is measured.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use iced::{mouse::Cursor, widget::canvas::Frame, Font, Pixels, Rectangle, Size, Theme};
+use iced_widget::{
+    canvas::Geometry,
+    core::{
+        layout::{self, Layout},
+        renderer::Style,
+        widget::Tree,
+        Renderer as _, Widget,
+    },
+    renderer::wgpu,
+};
+use plotters::{coord::Shift, prelude::*};
+use plotters_iced::{Chart, ChartWidget, Renderer};
+
+const LINES: i32 = 400;
+const SIZE: Size = Size::new(1200.0, 800.0);
+
+struct DenseMesh;
+
+impl Chart<()> for DenseMesh {
+    type State = ();
+
+    fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, _builder: ChartBuilder<DB>) {}
+
+    fn draw_chart<DB: DrawingBackend>(&self, _state: &Self::State, root: DrawingArea<DB, Shift>) {
+        let (w, h) = root.dim_in_pixel();
+        let (w, h) = (w as i32, h as i32);
+        for i in 0..LINES {
+            let x = i * w / LINES;
+            let y = i * h / LINES;
+            root.draw(&PathElement::new(vec![(x, 0), (x, h)], BLUE))
+                .unwrap();
+            root.draw(&PathElement::new(vec![(0, y), (w, y)], BLUE))
+                .unwrap();
+        }
+    }
+
+    fn draw<R: Renderer, F: Fn(&mut Frame)>(&self, renderer: &R, size: Size, f: F) -> Geometry {
+        // the tessellated meshes are dropped, the renderer of the benchmark only gets an empty
+        // geometry
+        let mut frame = Frame::Wgpu(wgpu::geometry::Frame::new(size));
+        f(&mut frame);
+        if let Frame::Wgpu(frame) = frame {
+            black_box(frame.into_primitive());
+        }
+        renderer.draw(size, |_| {})
+    }
+}
+
+fn draw_mesh(c: &mut Criterion) {
+    let mut renderer = iced::Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+        iced_tiny_skia::Backend::new(),
+        Font::DEFAULT,
+        Pixels(16.0),
+    ));
+    let node = layout::Node::new(SIZE);
+    let viewport = Rectangle::with_size(SIZE);
+
+    let mut group = c.benchmark_group("dense mesh");
+    for (name, batching) in [("batched", true), ("unbatched", false)] {
+        let widget = ChartWidget::new(DenseMesh).batching(batching);
+        let tree = Tree::new(&widget as &dyn Widget<(), Theme, iced::Renderer>);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                renderer.clear();
+                widget.draw(
+                    &tree,
+                    &mut renderer,
+                    &Theme::Light,
+                    &Style::default(),
+                    Layout::new(&node),
+                    Cursor::Unavailable,
+                    &viewport,
+                );
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, draw_mesh);
+criterion_main!(benches);
@@ -8,7 +8,7 @@ extern crate iced;
 extern crate plotters;
 extern crate sysinfo;
 
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, Utc};
 use iced::{
     alignment::{Horizontal, Vertical},
     executor, font,
@@ -64,14 +64,16 @@ impl Application for State {
     type Theme = Theme;
 
     fn new(_flags: Self::Flags) -> (Self, Command<Self::Message>) {
+        // draw texts of "sans-serif" in charts with the bundled font
+        plotters_iced::fonts::register("sans-serif", Font::with_name("Noto Sans"));
         (
             Self {
                 chart: Default::default(),
             },
             Command::batch([
-                font::load(include_bytes!("./fonts/notosans-regular.ttf").as_slice())
+                font::load(include_bytes!("../assets/fonts/notosans-regular.ttf").as_slice())
                     .map(Message::FontLoaded),
-                font::load(include_bytes!("./fonts/notosans-bold.ttf").as_slice())
+                font::load(include_bytes!("../assets/fonts/notosans-bold.ttf").as_slice())
                     .map(Message::FontLoaded),
             ]),
         )
@@ -82,11 +84,8 @@ impl Application for State {
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
-        match message {
-            Message::Tick => {
-                self.chart.update();
-            }
-            _ => {}
+        if let Message::Tick = message {
+            self.chart.update();
         }
         Command::none()
     }
@@ -179,7 +178,7 @@ impl SystemChart {
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         if !self.is_initialized() {
             Text::new("Loading...")
                 .horizontal_alignment(Horizontal::Center)
@@ -248,7 +247,7 @@ impl CpuUsageChart {
         self.cache.clear();
     }
 
-    fn view(&self, idx: usize, chart_height: f32) -> Element<Message> {
+    fn view(&self, idx: usize, chart_height: f32) -> Element<'_, Message> {
         Column::new()
             .width(Length::Fill)
             .height(Length::Shrink)
@@ -291,10 +290,7 @@ impl Chart<Message> for CpuUsageChart {
         let newest_time = self
             .data_points
             .front()
-            .unwrap_or(&(
-                Utc.from_utc_datetime(&chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap()),
-                0,
-            ))
+            .unwrap_or(&(DateTime::from_timestamp(0, 0).unwrap(), 0))
             .0;
         let oldest_time = newest_time - chrono::Duration::seconds(PLOT_SECONDS as i64);
         let mut chart = chart
@@ -79,9 +79,9 @@ impl Application for State {
         (
             Self { chart: None },
             Command::batch([
-                font::load(include_bytes!("./fonts/notosans-regular.ttf").as_slice())
+                font::load(include_bytes!("../assets/fonts/notosans-regular.ttf").as_slice())
                     .map(Message::FontLoaded),
-                font::load(include_bytes!("./fonts/notosans-bold.ttf").as_slice())
+                font::load(include_bytes!("../assets/fonts/notosans-bold.ttf").as_slice())
                     .map(Message::FontLoaded),
                 Command::perform(tokio::task::spawn_blocking(generate_data), |data| {
                     Message::DataLoaded(data.unwrap())
@@ -161,7 +161,7 @@ impl ExampleChart {
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         let chart = ChartWidget::new(self)
             .width(Length::Fill)
             .height(Length::Fill);
@@ -8,17 +8,16 @@ use iced::{
     event, executor,
     mouse::Cursor,
     widget::{
-        canvas::{self, Cache, Frame, Geometry},
+        canvas::{self, Cache, Frame, Geometry, Path},
         Column, Container, Text,
     },
-    Alignment, Application, Command, Element, Length, Point, Size, Theme,
+    Alignment, Application, Command, Element, Length, Size, Theme,
 };
-use plotters::{
-    coord::{types::RangedCoordf32, ReverseCoordTranslate},
-    prelude::*,
+use plotters::prelude::*;
+use plotters_iced::{
+    coords::{self, CaptureCoords},
+    style, Chart, ChartWidget, Renderer,
 };
-use plotters_iced::{Chart, ChartWidget, Renderer};
-use std::cell::RefCell;
 
 struct State {
     chart: ArtChart,
@@ -64,7 +63,7 @@ impl Application for State {
         Command::none()
     }
 
-    fn view(&self) -> Element<Self::Message> {
+    fn view(&self) -> Element<'_, Self::Message> {
         let content = Column::new()
             .spacing(20)
             .width(Length::Fill)
@@ -92,11 +91,10 @@ struct ArtChart {
     is_down: bool,
     current_position: Option<(f32, f32)>,
     initial_down_position: Option<(f32, f32)>,
-    spec: RefCell<Option<Cartesian2d<RangedCoordf32, RangedCoordf32>>>,
 }
 
 impl ArtChart {
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         let chart = ChartWidget::new(self)
             .width(Length::Fill)
             .height(Length::Fill);
@@ -104,9 +102,10 @@ impl ArtChart {
         chart.into()
     }
 
-    fn set_current_position(&mut self, p: Point) {
-        if let Some(spec) = self.spec.borrow().as_ref() {
-            self.current_position = spec.reverse_translate((p.x as i32, p.y as i32));
+    fn set_current_position(&mut self, p: Option<(f32, f32)>) {
+        self.current_position = p;
+        // the hover point is drawn by `draw_overlay`, only the preview needs a redraw
+        if self.is_down {
             self.cache.clear();
         }
     }
@@ -134,6 +133,7 @@ impl ArtChart {
         }
 
         self.is_down = new_is_down;
+        self.cache.clear();
     }
 }
 
@@ -153,7 +153,6 @@ impl Chart<Message> for ArtChart {
 
         const POINT_COLOR: RGBColor = colors::RED;
         const LINE_COLOR: RGBColor = colors::BLUE;
-        const HOVER_COLOR: RGBColor = colors::YELLOW;
         const PREVIEW_COLOR: RGBColor = colors::GREEN;
 
         let mut chart = builder
@@ -163,16 +162,19 @@ impl Chart<Message> for ArtChart {
             .build_cartesian_2d(0_f32..100_f32, 0_f32..100_f32)
             .expect("Failed to build chart");
 
+        // colors of the theme of the application
+        let appearance = style::appearance();
         chart
             .configure_mesh()
-            .bold_line_style(colors::BLACK.mix(0.1))
-            .light_line_style(colors::BLACK.mix(0.05))
-            .axis_style(ShapeStyle::from(colors::BLACK.mix(0.45)).stroke_width(1))
+            .bold_line_style(appearance.bold_mesh)
+            .light_line_style(appearance.light_mesh)
+            .axis_style(ShapeStyle::from(appearance.axis).stroke_width(1))
+            .x_label_style(("sans-serif", 15).into_font().color(&appearance.text))
             .y_labels(10)
             .y_label_style(
                 ("sans-serif", 15)
                     .into_font()
-                    .color(&colors::BLACK.mix(0.65))
+                    .color(&appearance.text)
                     .transform(FontTransform::Rotate90),
             )
             .y_label_formatter(&|y| format!("{}", y))
@@ -189,10 +191,7 @@ impl Chart<Message> for ArtChart {
 
         for line in &self.lines {
             chart
-                .draw_series(LineSeries::new(
-                    vec![line.0, line.1].into_iter(),
-                    LINE_COLOR.filled(),
-                ))
+                .draw_series(LineSeries::new(vec![line.0, line.1], LINE_COLOR.filled()))
                 .expect("Failed to draw line");
         }
 
@@ -211,23 +210,41 @@ impl Chart<Message> for ArtChart {
                 } else {
                     chart
                         .draw_series(LineSeries::new(
-                            vec![initial_p, current_p].into_iter(),
+                            vec![initial_p, current_p],
                             PREVIEW_COLOR.filled(),
                         ))
                         .expect("Failed to draw preview line");
                 }
             }
-        } else if let Some(current_p) = self.current_position {
-            chart
-                .draw_series(std::iter::once(Circle::new(
-                    current_p,
-                    5_i32,
-                    HOVER_COLOR.filled(),
-                )))
-                .expect("Failed to draw hover point");
         }
 
-        *self.spec.borrow_mut() = Some(chart.as_coord_spec().clone());
+        chart.capture_coords();
+    }
+
+    fn draw_overlay(
+        &self,
+        _state: &Self::State,
+        frame: &mut Frame,
+        bounds: iced::Rectangle,
+        cursor: Cursor,
+    ) {
+        if self.is_down || coords::under_cursor(bounds, cursor).is_none() {
+            return;
+        }
+        if let Some(position) = cursor.position_in(bounds) {
+            frame.fill(
+                &Path::circle(position, 5.0),
+                iced::Color::from_rgb8(255, 255, 0),
+            );
+        }
+    }
+
+    fn tooltip(&self, _state: &Self::State, (x, y): (f64, f64)) -> Option<String> {
+        let (px, py) = self
+            .points
+            .iter()
+            .find(|p| Self::nearby(**p, (x as f32, y as f32)))?;
+        Some(format!("({px:.1}, {py:.1})"))
     }
 
     fn update(
@@ -240,12 +257,10 @@ impl Chart<Message> for ArtChart {
         if let Cursor::Available(point) = cursor {
             match event {
                 canvas::Event::Mouse(evt) if bounds.contains(point) => {
-                    let p_origin = bounds.position();
-                    let p = point - p_origin;
-                    return (
-                        event::Status::Captured,
-                        Some(Message::MouseEvent(evt, Point::new(p.x, p.y))),
-                    );
+                    let p = coords::under_cursor(bounds, cursor)
+                        .and_then(|coords| coords.data_at(bounds, cursor))
+                        .map(|(x, y)| (x as f32, y as f32));
+                    return (event::Status::Captured, Some(Message::MouseEvent(evt, p)));
                 }
                 _ => {}
             }
@@ -256,7 +271,7 @@ impl Chart<Message> for ArtChart {
 
 #[derive(Debug)]
 enum Message {
-    MouseEvent(iced::mouse::Event, iced::Point),
+    MouseEvent(iced::mouse::Event, Option<(f32, f32)>),
 }
 
 fn main() -> iced::Result {
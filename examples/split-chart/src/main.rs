@@ -114,7 +114,7 @@ impl MyChart {
         Self
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         let chart = ChartWidget::new(self)
             .width(Length::Fill)
             .height(Length::Fill);
@@ -128,23 +128,30 @@ impl Chart<Message> for MyChart {
     // leave it empty
     fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, _builder: ChartBuilder<DB>) {}
 
-    fn draw_chart<DB: DrawingBackend>(&self, _state: &Self::State, root: DrawingArea<DB, Shift>) {
+    fn try_draw_chart<DB: DrawingBackend>(
+        &self,
+        _state: &Self::State,
+        root: DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
         let children = root.split_evenly((2, 2));
         for (i, area) in children.iter().enumerate() {
             let builder = ChartBuilder::on(area);
-            draw_chart(builder, i + 1);
+            draw_chart(builder, i + 1)?;
         }
+        Ok(())
     }
 }
 
-fn draw_chart<DB: DrawingBackend>(mut chart: ChartBuilder<DB>, power: usize) {
+fn draw_chart<DB: DrawingBackend>(
+    mut chart: ChartBuilder<DB>,
+    power: usize,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
     let mut chart = chart
         .margin(30)
         .caption(format!("y=x^{}", power), ("sans-serif", 22))
         .x_label_area_size(30)
         .y_label_area_size(30)
-        .build_cartesian_2d(-1f32..1f32, -1.2f32..1.2f32)
-        .unwrap();
+        .build_cartesian_2d(-1f32..1f32, -1.2f32..1.2f32)?;
 
     chart
         .configure_mesh()
@@ -156,15 +163,13 @@ fn draw_chart<DB: DrawingBackend>(mut chart: ChartBuilder<DB>, power: usize) {
         //         .color(&plotters::style::colors::BLACK.mix(0.8))
         //         .transform(FontTransform::RotateAngle(30.0)),
         // )
-        .draw()
-        .unwrap();
-
-    chart
-        .draw_series(LineSeries::new(
-            (-50..=50)
-                .map(|x| x as f32 / 50.0)
-                .map(|x| (x, x.powf(power as f32))),
-            &RED,
-        ))
-        .unwrap();
+        .draw()?;
+
+    chart.draw_series(LineSeries::new(
+        (-50..=50)
+            .map(|x| x as f32 / 50.0)
+            .map(|x| (x, x.powf(power as f32))),
+        &RED,
+    ))?;
+    Ok(())
 }
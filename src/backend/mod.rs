@@ -4,52 +4,251 @@
 // Copyright: 2022, Joylei <leingliu@gmail.com>
 // License: MIT
 
-use std::collections::HashSet;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::mem;
 
 use iced_graphics::core::text::Paragraph;
 use iced_widget::{
-    canvas,
+    canvas::{self, path::Builder, Cache},
     core::{
         alignment::{Horizontal, Vertical},
-        font, text, Font, Size,
+        image, text, Font, Rectangle, Size, Vector,
     },
+    renderer::Geometry,
     text::Shaping,
 };
-use once_cell::unsync::Lazy;
 use plotters_backend::{
-    text_anchor,
-    //FontTransform,
-    BackendColor,
-    BackendCoord,
-    BackendStyle,
-    BackendTextStyle,
-    DrawingBackend,
-    DrawingErrorKind,
-    FontFamily,
-    FontStyle,
+    text_anchor, BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend,
+    DrawingErrorKind, FontTransform,
 };
 
 use crate::error::Error;
+use crate::fonts::FontResolver;
+use crate::precise;
+use crate::stroke;
 use crate::utils::{cvt_color, cvt_stroke, CvtPoint};
 
+mod outline;
+mod paths;
+mod pixels;
+
+use paths::{Paint, PathBatch};
+use pixels::PixelBatch;
+
+/// A bitmap blitted by plotters, drawn as an iced image
+#[derive(Debug, Clone)]
+pub struct Bitmap {
+    pub(crate) handle: image::Handle,
+    pub(crate) bounds: Rectangle,
+}
+
+/// geometry drawn before bitmaps, and the bitmaps.
+///
+/// iced can not put images into a canvas frame, so the frame is split at bitmaps to keep the
+/// drawing order of plotters: the geometry of a segment is drawn below its bitmaps, which are
+/// below the geometry of the next segment.
+pub(crate) struct Segment {
+    /// geometry of the segment, until it is cached
+    frame: Option<canvas::Frame>,
+    cache: Cache,
+    pub bitmaps: Vec<Bitmap>,
+}
+
+impl Segment {
+    fn new(frame: canvas::Frame, bitmap: Bitmap) -> Self {
+        Self {
+            frame: Some(frame),
+            cache: Cache::new(),
+            bitmaps: vec![bitmap],
+        }
+    }
+
+    /// geometry drawn before the bitmaps
+    pub fn geometry(&mut self, renderer: &iced_widget::renderer::Renderer, size: Size) -> Geometry {
+        let frame = self.frame.take();
+        self.cache.draw(renderer, size, move |target| {
+            if let Some(frame) = frame {
+                *target = frame;
+            }
+        })
+    }
+}
+
+/// bitmaps wider or higher than this are rejected, as GPU textures are limited in size
+const MAX_BITMAP_SIZE: u32 = 8192;
+
+/// measured text sizes, by font and font size
+type TextSizes = HashMap<(Font, u32), HashMap<String, Size>>;
+
 /// The Iced drawing backend
 pub(crate) struct IcedChartBackend<'a, B> {
     frame: &'a mut canvas::Frame,
-    backend: &'a B,
     shaping: Shaping,
+    /// whether consecutive shapes and pixels are batched
+    batching: bool,
+    /// creates the frame of the geometry drawn after bitmaps
+    new_frame: &'a dyn Fn() -> canvas::Frame,
+    /// geometry and bitmaps drawn before the current frame
+    segments: &'a RefCell<Vec<Segment>>,
+    /// whether geometry was drawn since the last bitmaps
+    drawn: bool,
+    /// plotters measures the same labels many times during a drawing, so sizes are cached
+    text_sizes: RefCell<TextSizes>,
+    font_resolver: &'a FontResolver<'a>,
+    /// pending pixels of consecutive `draw_pixel` calls
+    pixels: PixelBatch,
+    /// pending path of consecutive shapes of the same style
+    paths: PathBatch,
+    _marker: PhantomData<&'a B>,
+}
+
+impl<'a, B> IcedChartBackend<'a, B> {
+    /// draws pending pixels and paths, must be called before drawing anything else unbatched to
+    /// keep the drawing order
+    #[inline]
+    fn flush(&mut self) {
+        self.paths.flush(self.frame);
+        self.flush_pixels();
+    }
+
+    /// draws pending pixels, as a bitmap if they are dense enough
+    fn flush_pixels(&mut self) {
+        if self.pixels.is_empty() {
+            return;
+        }
+        match self.pixels.flush(self.frame) {
+            Some(bitmap) => self.push_bitmap(bitmap),
+            None => self.drawn = true,
+        }
+    }
+
+    /// draws the bitmap above everything drawn so far, by moving the geometry drawn so far into a
+    /// new segment
+    fn push_bitmap(&mut self, bitmap: Bitmap) {
+        let mut segments = self.segments.borrow_mut();
+        match segments.last_mut() {
+            Some(segment) if !self.drawn => segment.bitmaps.push(bitmap),
+            _ => {
+                let frame = mem::replace(self.frame, (self.new_frame)());
+                segments.push(Segment::new(frame, bitmap));
+            }
+        }
+        self.drawn = false;
+    }
+
+    /// strokes a shape, batched with previous shapes of the same style.
+    ///
+    /// shapes are stroked at once with the current [`StrokeStyle`](stroke::StrokeStyle) if any,
+    /// so that each of them starts its own dash pattern. Translucent shapes are stroked at once
+    /// too, since crossings of a merged path would be blended only once.
+    fn stroke_shape<S: BackendStyle>(&mut self, style: &S, shape: impl FnOnce(&mut Builder)) {
+        self.flush_pixels();
+        self.drawn = true;
+        let frame = &mut *self.frame;
+        let paths = &mut self.paths;
+        let merge = self.batching && style.color().alpha >= 1.0;
+        stroke::with_current(|pattern| match (pattern, merge) {
+            (None, true) => {
+                let paint = Paint::Stroke {
+                    color: cvt_color(&style.color()),
+                    width: style.stroke_width() as f32,
+                };
+                shape(paths.builder(paint, frame));
+            }
+            (pattern, _) => {
+                paths.flush(frame);
+                let path = canvas::Path::new(shape);
+                let stroke = match pattern {
+                    Some(pattern) => pattern.apply(cvt_stroke(style)),
+                    None => cvt_stroke(style),
+                };
+                frame.stroke(&path, stroke);
+            }
+        });
+    }
+
+    /// fills a shape, batched with previous shapes of the same color.
+    ///
+    /// translucent shapes are filled at once, since overlapping areas of a merged path would be
+    /// blended only once. Merged shapes must have the same winding direction.
+    fn fill_shape(&mut self, color: BackendColor, shape: impl FnOnce(&mut Builder)) {
+        self.flush_pixels();
+        self.drawn = true;
+        if color.alpha < 1.0 || !self.batching {
+            self.paths.flush(self.frame);
+            self.frame
+                .fill(&canvas::Path::new(shape), cvt_color(&color));
+        } else {
+            shape(
+                self.paths
+                    .builder(Paint::Fill(cvt_color(&color)), self.frame),
+            );
+        }
+    }
+}
+
+impl<'a, B> Drop for IcedChartBackend<'a, B> {
+    fn drop(&mut self) {
+        self.flush();
+    }
 }
 
 impl<'a, B> IcedChartBackend<'a, B>
 where
     B: text::Renderer<Font = Font>,
 {
-    pub fn new(frame: &'a mut canvas::Frame, backend: &'a B, shaping: Shaping) -> Self {
+    pub fn new(
+        frame: &'a mut canvas::Frame,
+        new_frame: &'a dyn Fn() -> canvas::Frame,
+        shaping: Shaping,
+        batching: bool,
+        segments: &'a RefCell<Vec<Segment>>,
+        font_resolver: &'a FontResolver<'a>,
+    ) -> Self {
         Self {
             frame,
-            backend,
             shaping,
+            batching,
+            new_frame,
+            segments,
+            drawn: false,
+            text_sizes: Default::default(),
+            font_resolver,
+            pixels: Default::default(),
+            paths: Default::default(),
+            _marker: PhantomData,
         }
     }
+
+    #[inline]
+    fn style_to_font<S: BackendTextStyle>(&self, style: &S) -> Font {
+        (self.font_resolver)(style.family(), style.style())
+    }
+
+    /// measures the size of the text without any transform
+    fn measure_text(&self, text: &str, font: Font, size: f32) -> Size {
+        let mut text_sizes = self.text_sizes.borrow_mut();
+        let sizes = text_sizes.entry((font, size.to_bits())).or_default();
+        if let Some(size) = sizes.get(text) {
+            return *size;
+        }
+        let mut p = B::Paragraph::default();
+        p.update(iced_widget::core::text::Text {
+            content: text,
+            bounds: Size::INFINITY,
+            size: size.into(),
+            line_height: Default::default(),
+            font,
+            horizontal_alignment: Horizontal::Left,
+            vertical_alignment: Vertical::Top,
+            shaping: self.shaping,
+        });
+        let bounds = p.min_bounds();
+        sizes.insert(text.to_owned(), bounds);
+        bounds
+    }
 }
 
 impl<'a, B> DrawingBackend for IcedChartBackend<'a, B>
@@ -68,6 +267,7 @@ where
     }
 
     fn present(&mut self) -> Result<(), DrawingErrorKind<Error>> {
+        self.flush();
         Ok(())
     }
 
@@ -80,8 +280,11 @@ where
         if color.alpha == 0.0 {
             return Ok(());
         }
-        self.frame
-            .fill_rectangle(point.cvt_point(), Size::new(1.0, 1.0), cvt_color(&color));
+        self.paths.flush(self.frame);
+        self.pixels.push(point, color);
+        if !self.batching {
+            self.flush_pixels();
+        }
         Ok(())
     }
 
@@ -95,8 +298,10 @@ where
         if style.color().alpha == 0.0 {
             return Ok(());
         }
-        let line = canvas::Path::line(from.cvt_point(), to.cvt_point());
-        self.frame.stroke(&line, cvt_stroke(style));
+        self.stroke_shape(style, |builder| {
+            builder.move_to(from.cvt_point());
+            builder.line_to(to.cvt_point());
+        });
         Ok(())
     }
 
@@ -111,18 +316,22 @@ where
         if style.color().alpha == 0.0 {
             return Ok(());
         }
-        let height = (bottom_right.1 - upper_left.1) as f32;
-        let width = (bottom_right.0 - upper_left.0) as f32;
-        let upper_left = upper_left.cvt_point();
+        // merged rectangles must have the same winding direction, whatever corners are given
+        let (x0, x1) = (
+            upper_left.0.min(bottom_right.0),
+            upper_left.0.max(bottom_right.0),
+        );
+        let (y0, y1) = (
+            upper_left.1.min(bottom_right.1),
+            upper_left.1.max(bottom_right.1),
+        );
+        let (width, height) = ((x1 - x0) as f32, (y1 - y0) as f32);
+        let upper_left = (x0, y0).cvt_point();
+        let rect = |builder: &mut Builder| builder.rectangle(upper_left, Size::new(width, height));
         if fill {
-            self.frame.fill_rectangle(
-                upper_left,
-                Size::new(width, height),
-                cvt_color(&style.color()),
-            );
+            self.fill_shape(style.color(), rect);
         } else {
-            let rect = canvas::Path::rectangle(upper_left, Size::new(width, height));
-            self.frame.stroke(&rect, cvt_stroke(style));
+            self.stroke_shape(style, rect);
         }
 
         Ok(())
@@ -137,17 +346,16 @@ where
         if style.color().alpha == 0.0 {
             return Ok(());
         }
-        let path = canvas::Path::new(move |builder| {
-            for (i, point) in path.into_iter().enumerate() {
-                if i > 0 {
-                    builder.line_to(point.cvt_point());
-                } else {
-                    builder.move_to(point.cvt_point());
+        // paths drawn by `PreciseDrawingArea` come with sub-pixel precision points
+        match precise::take_path() {
+            Some(points) => {
+                if points.iter().flatten().any(|v| !v.is_finite()) {
+                    return Err(DrawingErrorKind::DrawingError(Error::InvalidGeometry));
                 }
+                self.stroke_shape(style, move |builder| add_lines(builder, points))
             }
-        });
-
-        self.frame.stroke(&path, cvt_stroke(style));
+            None => self.stroke_shape(style, move |builder| add_lines(builder, path)),
+        }
         Ok(())
     }
 
@@ -163,12 +371,12 @@ where
             return Ok(());
         }
 
-        let circle = canvas::Path::circle(center.cvt_point(), radius as f32);
+        let circle = |builder: &mut Builder| builder.circle(center.cvt_point(), radius as f32);
 
         if fill {
-            self.frame.fill(&circle, cvt_color(&style.color()));
+            self.fill_shape(style.color(), circle);
         } else {
-            self.frame.stroke(&circle, cvt_stroke(style));
+            self.stroke_shape(style, circle);
         }
 
         Ok(())
@@ -180,9 +388,12 @@ where
         vert: I,
         style: &S,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.flush();
         if style.color().alpha == 0.0 {
             return Ok(());
         }
+        self.drawn = true;
+        // polygons may have any winding direction, so they are never merged
         let path = canvas::Path::new(move |builder| {
             for (i, point) in vert.into_iter().enumerate() {
                 if i > 0 {
@@ -204,9 +415,11 @@ where
         style: &S,
         pos: BackendCoord,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.flush();
         if style.color().alpha == 0.0 {
             return Ok(());
         }
+        self.drawn = true;
         let horizontal_alignment = match style.anchor().h_pos {
             text_anchor::HPos::Left => Horizontal::Left,
             text_anchor::HPos::Right => Horizontal::Right,
@@ -217,10 +430,38 @@ where
             text_anchor::VPos::Center => Vertical::Center,
             text_anchor::VPos::Bottom => Vertical::Bottom,
         };
-        let font = style_to_font(style);
+        let font = self.style_to_font(style);
         let pos = pos.cvt_point();
 
-        //let (w, h) = self.estimate_text_size(text, style)?;
+        // iced does not rotate glyphs of texts, rotated texts are filled as outlines instead
+        if let Some(angle) = rotation(style.transform()) {
+            let (path, Size { width, height }) = outline::text_outline(
+                text,
+                font,
+                (style.size() as f32).into(),
+                Default::default(),
+                self.shaping,
+            );
+            let dx = match horizontal_alignment {
+                Horizontal::Left => 0.0,
+                Horizontal::Center => -width / 2.0,
+                Horizontal::Right => -width,
+            };
+            let dy = match vertical_alignment {
+                Vertical::Top => 0.0,
+                Vertical::Center => -height / 2.0,
+                Vertical::Bottom => -height,
+            };
+            let color = cvt_color(&style.color());
+            self.frame.with_save(move |frame| {
+                frame.translate(Vector::new(pos.x, pos.y));
+                frame.rotate(angle.to_radians() as f32);
+                frame.translate(Vector::new(dx, dy));
+                frame.fill(&path, color);
+            });
+            return Ok(());
+        }
+
         let text = canvas::Text {
             content: text.to_owned(),
             position: pos,
@@ -232,25 +473,6 @@ where
             vertical_alignment,
             shaping: self.shaping,
         };
-        //TODO: fix rotation until text rotation is supported by Iced
-        // let rotate = match style.transform() {
-        //     FontTransform::None => None,
-        //     FontTransform::Rotate90 => Some(90.0),
-        //     FontTransform::Rotate180 => Some(180.0),
-        //     FontTransform::Rotate270 => Some(270.0),
-        //     FontTransform::RotateAngle(angle) => Some(angle),
-        // };
-        // if let Some(rotate) = rotate {
-        //     dbg!(rotate);
-        //     self.frame.with_save(move |frame| {
-        //         frame.fill_text(text);
-        //         frame.translate(Vector::new(pos.x + w as f32 / 2.0, pos.y + h as f32 / 2.0));
-        //         let angle = 2.0 * std::f32::consts::PI * rotate / 360.0;
-        //         frame.rotate(angle);
-        //     });
-        // } else {
-        //     self.frame.fill_text(text);
-        // }
         self.frame.fill_text(text);
 
         Ok(())
@@ -262,71 +484,277 @@ where
         text: &str,
         style: &S,
     ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
-        let font = style_to_font(style);
-        let bounds = self.frame.size();
-        let horizontal_alignment = match style.anchor().h_pos {
-            text_anchor::HPos::Left => Horizontal::Left,
-            text_anchor::HPos::Right => Horizontal::Right,
-            text_anchor::HPos::Center => Horizontal::Center,
-        };
-        let vertical_alignment = match style.anchor().v_pos {
-            text_anchor::VPos::Top => Vertical::Top,
-            text_anchor::VPos::Center => Vertical::Center,
-            text_anchor::VPos::Bottom => Vertical::Bottom,
+        let font = self.style_to_font(style);
+        let size = self.measure_text(text, font, style.size() as f32);
+        let (width, height) = match rotation(style.transform()) {
+            Some(angle) => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let (w, h) = (size.width as f64, size.height as f64);
+                (w * cos.abs() + h * sin.abs(), w * sin.abs() + h * cos.abs())
+            }
+            None => (size.width as f64, size.height as f64),
         };
-
-        let mut p = B::Paragraph::default();
-        p.update(iced_widget::core::text::Text {
-            content: text,
-            bounds,
-            size: self.backend.default_size(),
-            line_height: Default::default(),
-            font,
-            horizontal_alignment,
-            vertical_alignment,
-            shaping: self.shaping,
-        });
-        let size = p.min_bounds();
-        Ok((size.width as u32, size.height as u32))
+        Ok((width.round() as u32, height.round() as u32))
     }
 
     #[inline]
     fn blit_bitmap(
         &mut self,
-        _pos: BackendCoord,
-        (_iw, _ih): (u32, u32),
-        _src: &[u8],
+        pos: BackendCoord,
+        (iw, ih): (u32, u32),
+        src: &[u8],
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        // Not supported yet (rendering ignored)
-        // Notice: currently Iced has limitations, because widgets are not rendered in the order of creation, and different primitives go to different render pipelines.
-
+        self.flush();
+        if iw == 0 || ih == 0 {
+            return Ok(());
+        }
+        if iw > MAX_BITMAP_SIZE || ih > MAX_BITMAP_SIZE {
+            return Err(DrawingErrorKind::DrawingError(Error::BitmapTooLarge {
+                width: iw,
+                height: ih,
+            }));
+        }
+        let pixels = rgba_pixels(iw, ih, src).ok_or(DrawingErrorKind::DrawingError(
+            Error::InvalidBitmap {
+                width: iw,
+                height: ih,
+                len: src.len(),
+            },
+        ))?;
+        let bounds = Rectangle::new(pos.cvt_point(), Size::new(iw as f32, ih as f32));
+        self.push_bitmap(Bitmap {
+            handle: image::Handle::from_pixels(iw, ih, pixels),
+            bounds,
+        });
         Ok(())
     }
 }
 
-fn style_to_font<S: BackendTextStyle>(style: &S) -> Font {
-    // iced font family requires static str
-    static mut FONTS: Lazy<HashSet<String>> = Lazy::new(HashSet::new);
-
-    Font {
-        family: match style.family() {
-            FontFamily::Serif => font::Family::Serif,
-            FontFamily::SansSerif => font::Family::SansSerif,
-            FontFamily::Monospace => font::Family::Monospace,
-            FontFamily::Name(s) => {
-                let s = unsafe {
-                    if !FONTS.contains(s) {
-                        FONTS.insert(String::from(s));
-                    }
-                    FONTS.get(s).unwrap().as_str()
-                };
-                font::Family::Name(s)
+/// adds connected lines through the points
+fn add_lines<P: CvtPoint>(builder: &mut Builder, points: impl IntoIterator<Item = P>) {
+    for (i, point) in points.into_iter().enumerate() {
+        if i > 0 {
+            builder.line_to(point.cvt_point());
+        } else {
+            builder.move_to(point.cvt_point());
+        }
+    }
+}
+
+/// converts RGB or RGBA buffer into RGBA pixels
+fn rgba_pixels(width: u32, height: u32, src: &[u8]) -> Option<Vec<u8>> {
+    let count = width as usize * height as usize;
+    if src.len() == count * 4 {
+        Some(src.to_vec())
+    } else if src.len() == count * 3 {
+        let mut pixels = Vec::with_capacity(count * 4);
+        for rgb in src.chunks_exact(3) {
+            pixels.extend_from_slice(rgb);
+            pixels.push(u8::MAX);
+        }
+        Some(pixels)
+    } else {
+        None
+    }
+}
+
+/// rotation in degrees, clockwise
+fn rotation(transform: FontTransform) -> Option<f64> {
+    match transform {
+        FontTransform::None => None,
+        FontTransform::Rotate90 => Some(90.0),
+        FontTransform::Rotate180 => Some(180.0),
+        FontTransform::Rotate270 => Some(270.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iced_widget::core::Pixels;
+    use plotters::style::{FontDesc, TextStyle};
+    use plotters_backend::{FontFamily, FontStyle};
+
+    use super::*;
+
+    const BLACK: BackendColor = BackendColor {
+        alpha: 1.0,
+        rgb: (0, 0, 0),
+    };
+
+    /// draws with the backend, returns the frame and the bitmaps of the segments
+    fn render(
+        batching: bool,
+        f: impl FnOnce(&mut IcedChartBackend<'_, iced_widget::renderer::Renderer>),
+    ) -> (canvas::Frame, Vec<usize>) {
+        let renderer = iced_widget::renderer::Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+            iced_tiny_skia::Backend::new(),
+            Font::DEFAULT,
+            Pixels(16.0),
+        ));
+        let size = Size::new(10.0, 10.0);
+        let mut frame = canvas::Frame::new(&renderer, size);
+        let new_frame = || canvas::Frame::new(&renderer, size);
+        let segments = RefCell::new(Vec::new());
+        let font_resolver = |_: FontFamily<'_>, _: FontStyle| Font::DEFAULT;
+        let mut backend = IcedChartBackend::new(
+            &mut frame,
+            &new_frame,
+            Shaping::Basic,
+            batching,
+            &segments,
+            &font_resolver,
+        );
+        f(&mut backend);
+        drop(backend);
+        let segments = segments
+            .into_inner()
+            .iter()
+            .map(|segment| segment.bitmaps.len())
+            .collect();
+        (frame, segments)
+    }
+
+    /// draws with the backend, returns the bitmaps of the segments
+    fn draw(
+        f: impl FnOnce(&mut IcedChartBackend<'_, iced_widget::renderer::Renderer>),
+    ) -> Vec<usize> {
+        render(true, f).1
+    }
+
+    /// draws with the backend, returns the number of paths drawn into the frame
+    fn paths(
+        batching: bool,
+        f: impl FnOnce(&mut IcedChartBackend<'_, iced_widget::renderer::Renderer>),
+    ) -> usize {
+        use iced_graphics::Primitive;
+
+        fn count(primitive: &iced_tiny_skia::Primitive) -> usize {
+            match primitive {
+                Primitive::Group { primitives } => primitives.iter().map(count).sum(),
+                Primitive::Clip { content, .. } | Primitive::Transform { content, .. } => {
+                    count(content)
+                }
+                Primitive::Custom(_) => 1,
+                _ => 0,
+            }
+        }
+
+        match render(batching, f).0.into_geometry() {
+            canvas::Geometry::TinySkia(primitive) => count(&primitive),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn segments_test() {
+        let segments = draw(|backend| {
+            backend.draw_line((0, 0), (9, 9), &BLACK).unwrap();
+            backend.blit_bitmap((0, 0), (2, 2), &[0; 12]).unwrap();
+            backend.blit_bitmap((2, 2), (2, 2), &[0; 12]).unwrap();
+            backend.draw_line((0, 9), (9, 0), &BLACK).unwrap();
+            backend.blit_bitmap((4, 4), (2, 2), &[0; 16]).unwrap();
+        });
+        // consecutive bitmaps are drawn together, lines drawn after bitmaps are above them
+        assert_eq!(segments, vec![2, 1]);
+
+        let segments = draw(|backend| {
+            backend.draw_line((0, 0), (9, 9), &BLACK).unwrap();
+        });
+        assert!(segments.is_empty());
+    }
+
+    #[test]
+    fn batching_test() {
+        let translucent = BackendColor {
+            alpha: 0.5,
+            rgb: (0, 0, 0),
+        };
+        let lines = |color: BackendColor| {
+            move |backend: &mut IcedChartBackend<'_, iced_widget::renderer::Renderer>| {
+                for i in 0..4 {
+                    backend.draw_line((i, 0), (i, 9), &color).unwrap();
+                }
+            }
+        };
+        // opaque strokes of the same style are merged into one path
+        assert_eq!(paths(true, lines(BLACK)), 1);
+        assert_eq!(paths(false, lines(BLACK)), 4);
+        // crossings of translucent strokes must be blended twice
+        assert_eq!(paths(true, lines(translucent)), 4);
+    }
+
+    fn text_style(size: f64, transform: FontTransform) -> TextStyle<'static> {
+        FontDesc::new(FontFamily::SansSerif, size, FontStyle::Normal)
+            .transform(transform)
+            .into()
+    }
+
+    #[test]
+    fn text_size_test() {
+        draw(|backend| {
+            let small = backend
+                .estimate_text_size("label", &text_style(10.0, FontTransform::None))
+                .unwrap();
+            let large = backend
+                .estimate_text_size("label", &text_style(20.0, FontTransform::None))
+                .unwrap();
+            // sizes are cached per font size
+            assert!(small.0 < large.0 && small.1 < large.1);
+            assert_eq!(backend.text_sizes.borrow().len(), 2);
+
+            let font = backend.style_to_font(&text_style(20.0, FontTransform::None));
+            let cached = backend.measure_text("label", font, 20.0);
+            backend.text_sizes.borrow_mut().clear();
+            assert_eq!(backend.measure_text("label", font, 20.0), cached);
+            assert_eq!(
+                (cached.width.round() as u32, cached.height.round() as u32),
+                large
+            );
+        });
+    }
+
+    #[test]
+    fn rotated_text_test() {
+        draw(|backend| {
+            let (width, height) = backend
+                .estimate_text_size("label", &text_style(20.0, FontTransform::None))
+                .unwrap();
+            assert!(width > height);
+            for transform in [FontTransform::Rotate90, FontTransform::Rotate270] {
+                let style = text_style(20.0, transform);
+                assert_eq!(
+                    backend.estimate_text_size("label", &style).unwrap(),
+                    (height, width)
+                );
+                backend.draw_text("label", &style, (5, 5)).unwrap();
             }
-        },
-        weight: match style.style() {
-            FontStyle::Bold => font::Weight::Bold,
-            _ => font::Weight::Normal,
-        },
-        ..Font::DEFAULT
+            let style = text_style(20.0, FontTransform::Rotate180);
+            assert_eq!(
+                backend.estimate_text_size("label", &style).unwrap(),
+                (width, height)
+            );
+            backend.draw_text("label", &style, (5, 5)).unwrap();
+        });
+    }
+
+    #[test]
+    fn pixels_test() {
+        // dense pixels are drawn as a bitmap, below the line drawn after them
+        let segments = draw(|backend| {
+            for i in 0..64 {
+                backend.draw_pixel((i % 8, i / 8), BLACK).unwrap();
+            }
+            backend.draw_line((0, 0), (9, 9), &BLACK).unwrap();
+        });
+        assert_eq!(segments, vec![1]);
+
+        // sparse pixels are drawn as rectangles
+        let segments = draw(|backend| {
+            for i in 0..64 {
+                backend.draw_pixel((i * 100, 0), BLACK).unwrap();
+            }
+            backend.draw_line((0, 0), (9, 9), &BLACK).unwrap();
+        });
+        assert!(segments.is_empty());
     }
 }
@@ -0,0 +1,88 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! text outlines, used to draw transformed texts since iced does not rotate glyphs
+
+use std::cell::RefCell;
+
+use iced_graphics::text::{
+    cosmic_text::{self, CacheKey, Command, SwashCache},
+    font_system, measure, to_attributes, to_shaping,
+};
+use iced_widget::{
+    canvas::{path::Builder, Path},
+    core::{text::LineHeight, Font, Pixels, Point, Size},
+    text::Shaping,
+};
+
+thread_local! {
+    static SWASH_CACHE: RefCell<SwashCache> = RefCell::new(SwashCache::new());
+}
+
+/// lays out the text and converts its glyphs into a single path.
+///
+/// The top left corner of the text box is placed at the origin; returns the path and the size of
+/// the text box.
+pub(crate) fn text_outline(
+    content: &str,
+    font: Font,
+    size: Pixels,
+    line_height: LineHeight,
+    shaping: Shaping,
+) -> (Path, Size) {
+    let mut font_system = font_system().write().expect("Write font system");
+    let font_system = font_system.raw();
+
+    let mut buffer = cosmic_text::Buffer::new(
+        font_system,
+        cosmic_text::Metrics::new(size.0, line_height.to_absolute(size).0),
+    );
+    buffer.set_size(font_system, f32::INFINITY, f32::INFINITY);
+    buffer.set_text(
+        font_system,
+        content,
+        to_attributes(font),
+        to_shaping(shaping),
+    );
+    let bounds = measure(&buffer);
+
+    let path = SWASH_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let mut builder = Builder::new();
+        for run in buffer.layout_runs() {
+            for glyph in run.glyphs {
+                let (cache_key, _, _) =
+                    CacheKey::new(glyph.font_id, glyph.glyph_id, glyph.font_size, (0.0, 0.0));
+                let commands = match cache.get_outline_commands(font_system, cache_key) {
+                    Some(commands) => commands,
+                    None => continue,
+                };
+                // glyph outlines are y-up, relative to the baseline
+                let x = glyph.x + glyph.font_size * glyph.x_offset;
+                let y = run.line_y + glyph.y - glyph.font_size * glyph.y_offset;
+                append_commands(&mut builder, commands, Point::new(x, y));
+            }
+        }
+        builder.build()
+    });
+
+    (path, bounds)
+}
+
+fn append_commands(builder: &mut Builder, commands: &[Command], origin: Point) {
+    let point = |x: f32, y: f32| Point::new(origin.x + x, origin.y - y);
+    for command in commands {
+        match *command {
+            Command::MoveTo(p) => builder.move_to(point(p.x, p.y)),
+            Command::LineTo(p) => builder.line_to(point(p.x, p.y)),
+            Command::CurveTo(c1, c2, p) => {
+                builder.bezier_curve_to(point(c1.x, c1.y), point(c2.x, c2.y), point(p.x, p.y))
+            }
+            Command::QuadTo(c, p) => builder.quadratic_curve_to(point(c.x, c.y), point(p.x, p.y)),
+            Command::Close => builder.close(),
+        }
+    }
+}
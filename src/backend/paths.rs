@@ -0,0 +1,56 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! batching of paths, to stroke or fill consecutive shapes of the same style at once
+
+use iced_widget::{
+    canvas::{self, path::Builder},
+    core::Color,
+};
+
+/// how a batched path is painted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Paint {
+    Stroke { color: Color, width: f32 },
+    Fill(Color),
+}
+
+/// consecutive shapes painted the same way, merged into one path
+#[derive(Default)]
+pub(super) struct PathBatch {
+    pending: Option<(Paint, Builder)>,
+}
+
+impl PathBatch {
+    /// returns the path builder for shapes painted by `paint`.
+    ///
+    /// the pending path is drawn first if it is painted differently.
+    pub fn builder(&mut self, paint: Paint, frame: &mut canvas::Frame) -> &mut Builder {
+        if !matches!(self.pending, Some((p, _)) if p == paint) {
+            self.flush(frame);
+        }
+        &mut self
+            .pending
+            .get_or_insert_with(|| (paint, Builder::new()))
+            .1
+    }
+
+    /// draws the pending path
+    pub fn flush(&mut self, frame: &mut canvas::Frame) {
+        if let Some((paint, builder)) = self.pending.take() {
+            let path = builder.build();
+            match paint {
+                Paint::Stroke { color, width } => frame.stroke(
+                    &path,
+                    canvas::Stroke::default()
+                        .with_color(color)
+                        .with_width(width),
+                ),
+                Paint::Fill(color) => frame.fill(&path, color),
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! batching of pixels, to avoid one rectangle per pixel for pixel based elements
+//!
+//! dense batches become bitmaps, which split the frame like blitted bitmaps, so shapes drawn
+//! after the pixels stay above them.
+
+use iced_widget::{
+    canvas,
+    core::{image, Rectangle, Size},
+};
+use plotters_backend::{BackendColor, BackendCoord};
+
+use super::{Bitmap, MAX_BITMAP_SIZE};
+use crate::utils::{cvt_color, CvtPoint};
+
+/// batches with fewer pixels are drawn as rectangles
+const MIN_IMAGE_PIXELS: usize = 64;
+
+/// batches covering more than this many times their pixel count, or larger than
+/// [`MAX_BITMAP_SIZE`] are drawn as rectangles
+const MAX_IMAGE_SPARSENESS: usize = 8;
+
+/// consecutive pixels drawn by plotters
+#[derive(Default)]
+pub(super) struct PixelBatch {
+    pixels: Vec<(BackendCoord, BackendColor)>,
+}
+
+impl PixelBatch {
+    #[inline]
+    pub fn push(&mut self, point: BackendCoord, color: BackendColor) {
+        self.pixels.push((point, color));
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// returns the batched pixels as one image if they are dense enough, otherwise draws them as
+    /// rectangles
+    pub fn flush(&mut self, frame: &mut canvas::Frame) -> Option<Bitmap> {
+        let bitmap = self.to_bitmap();
+        if bitmap.is_none() {
+            for (point, color) in self.pixels.iter() {
+                frame.fill_rectangle(point.cvt_point(), Size::new(1.0, 1.0), cvt_color(color));
+            }
+        }
+        self.pixels.clear();
+        bitmap
+    }
+
+    fn to_bitmap(&self) -> Option<Bitmap> {
+        if self.pixels.len() < MIN_IMAGE_PIXELS {
+            return None;
+        }
+        let (mut x0, mut y0) = self.pixels[0].0;
+        let (mut x1, mut y1) = (x0, y0);
+        for ((x, y), _) in self.pixels.iter() {
+            x0 = x0.min(*x);
+            y0 = y0.min(*y);
+            x1 = x1.max(*x);
+            y1 = y1.max(*y);
+        }
+        // in i64, as the extent of extreme coordinates overflows i32
+        let width = (x1 as i64 - x0 as i64 + 1) as u64;
+        let height = (y1 as i64 - y0 as i64 + 1) as u64;
+        if width > MAX_BITMAP_SIZE as u64
+            || height > MAX_BITMAP_SIZE as u64
+            || width * height > (self.pixels.len() * MAX_IMAGE_SPARSENESS) as u64
+        {
+            return None;
+        }
+        let (width, height) = (width as usize, height as usize);
+
+        let mut buf = vec![0u8; width * height * 4];
+        for ((x, y), color) in self.pixels.iter() {
+            let offset = ((y - y0) as usize * width + (x - x0) as usize) * 4;
+            blend(&mut buf[offset..offset + 4], color);
+        }
+        Some(Bitmap {
+            handle: image::Handle::from_pixels(width as u32, height as u32, buf),
+            bounds: Rectangle::new((x0, y0).cvt_point(), Size::new(width as f32, height as f32)),
+        })
+    }
+}
+
+/// blends the color over the RGBA pixel
+fn blend(dst: &mut [u8], color: &BackendColor) {
+    let (r, g, b) = color.rgb;
+    let src_a = color.alpha.clamp(0.0, 1.0);
+    let dst_a = dst[3] as f64 / 255.0;
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    if out_a <= 0.0 {
+        return;
+    }
+    for (d, s) in dst.iter_mut().zip([r, g, b]) {
+        let v = (s as f64 * src_a + *d as f64 * dst_a * (1.0 - src_a)) / out_a;
+        *d = v.round() as u8;
+    }
+    dst[3] = (out_a * 255.0).round() as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_test() {
+        let mut pixel = [0u8; 4];
+        blend(
+            &mut pixel,
+            &BackendColor {
+                alpha: 1.0,
+                rgb: (10, 20, 30),
+            },
+        );
+        assert_eq!(pixel, [10, 20, 30, 255]);
+        blend(
+            &mut pixel,
+            &BackendColor {
+                alpha: 0.5,
+                rgb: (110, 120, 130),
+            },
+        );
+        assert_eq!(pixel, [60, 70, 80, 255]);
+    }
+
+    #[test]
+    fn sparse_pixels_test() {
+        let color = BackendColor {
+            alpha: 1.0,
+            rgb: (0, 0, 0),
+        };
+        let mut batch = PixelBatch::default();
+        for i in 0..MIN_IMAGE_PIXELS as i32 {
+            batch.push((i * 100, 0), color);
+        }
+        assert!(batch.to_bitmap().is_none());
+
+        let mut batch = PixelBatch::default();
+        for i in 0..MIN_IMAGE_PIXELS as i32 {
+            batch.push((i % 8, i / 8), color);
+        }
+        let bitmap = batch.to_bitmap().expect("dense pixels");
+        assert_eq!(bitmap.bounds.size(), Size::new(8.0, 8.0));
+    }
+
+    #[test]
+    fn large_pixels_test() {
+        let color = BackendColor {
+            alpha: 1.0,
+            rgb: (0, 0, 0),
+        };
+        // a dense line wider than the maximum bitmap size
+        let mut batch = PixelBatch::default();
+        for x in 0..MAX_BITMAP_SIZE as i32 + 1 {
+            batch.push((x, 0), color);
+        }
+        assert!(batch.to_bitmap().is_none());
+
+        // the extent overflows i32
+        let mut batch = PixelBatch::default();
+        for i in 0..MIN_IMAGE_PIXELS as i32 {
+            let x = if i % 2 == 0 { i32::MIN } else { i32::MAX };
+            batch.push((x, i), color);
+        }
+        assert!(batch.to_bitmap().is_none());
+    }
+}
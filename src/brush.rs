@@ -0,0 +1,286 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Box selection
+//!
+//! [`crate::ChartWidget::on_select`] lets users drag a rectangle over the plotting area, and
+//! publishes the selected ranges of data when the mouse button is released. `Escape` cancels the
+//! selection. The chart must capture its coordinates, see [`crate::coords`].
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::brush::BrushMode;
+//!
+//! ChartWidget::new(chart).on_select(BrushMode::X, |selection| Message::TimeWindow(selection.x))
+//! ```
+
+use std::ops::Range;
+
+use iced_widget::{
+    canvas::{Event, Frame, Path, Stroke},
+    core::{
+        event::Status,
+        keyboard::{self, key::Named, Key},
+        mouse::{self, Cursor},
+        Color, Point, Rectangle, Size,
+    },
+};
+
+use plotters::style::Color as _;
+
+use crate::coords::{Axis, Coords};
+use crate::style::Appearance;
+use crate::utils::cvt_color;
+
+/// opacities of the selected rectangle, which has the color of the first series
+const FILL_ALPHA: f32 = 0.2;
+const STROKE_ALPHA: f32 = 0.8;
+
+/// selections smaller than this many pixels are ignored, e.g. clicks
+const MIN_SIZE: f32 = 3.0;
+
+/// axes selected by dragging
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BrushMode {
+    /// select a range of the x axis
+    X,
+    /// select a range of the y axis
+    Y,
+    /// select ranges of both axes
+    #[default]
+    XY,
+}
+
+/// ranges of data selected by dragging, in ascending order
+///
+/// the range of an axis which is not selected is the range of the whole axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection {
+    /// selected range of the x axis
+    pub x: Range<f64>,
+    /// selected range of the y axis
+    pub y: Range<f64>,
+}
+
+/// an ongoing selection
+#[derive(Debug)]
+struct Dragging {
+    /// positions relative to the chart
+    origin: Point,
+    current: Point,
+    coords: Coords,
+}
+
+/// tracks the selection of a chart
+#[derive(Debug, Default)]
+pub(crate) struct Brush {
+    mode: BrushMode,
+    dragging: Option<Dragging>,
+}
+
+impl Brush {
+    /// updates the selection on user input, returns the selection once it is done
+    pub fn update(
+        &mut self,
+        mode: BrushMode,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+        coords: &[Coords],
+    ) -> (Status, Option<Selection>) {
+        self.mode = mode;
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let Some(position) = cursor.position_in(bounds) else {
+                    return (Status::Ignored, None);
+                };
+                let Some(coords) = coords
+                    .iter()
+                    .find(|coords| coords.plotting_area().contains(position))
+                else {
+                    return (Status::Ignored, None);
+                };
+                self.dragging = Some(Dragging {
+                    origin: position,
+                    current: position,
+                    coords: coords.clone(),
+                });
+                (Status::Captured, None)
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let (Some(dragging), Some(position)) =
+                    (&mut self.dragging, cursor.position_from(bounds.position()))
+                else {
+                    return (Status::Ignored, None);
+                };
+                dragging.current = clamp(position, dragging.coords.plotting_area());
+                (Status::Captured, None)
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                match self.dragging.take() {
+                    Some(dragging) => (Status::Captured, selection(mode, &dragging)),
+                    None => (Status::Ignored, None),
+                }
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: Key::Named(Named::Escape),
+                ..
+            }) => match self.dragging.take() {
+                Some(_) => (Status::Captured, None),
+                None => (Status::Ignored, None),
+            },
+            _ => (Status::Ignored, None),
+        }
+    }
+
+    /// whether a selection is ongoing
+    pub fn is_dragging(&self) -> bool {
+        self.dragging.is_some()
+    }
+
+    /// draws the ongoing selection
+    pub fn draw(&self, frame: &mut Frame, appearance: &Appearance) {
+        let Some(dragging) = &self.dragging else {
+            return;
+        };
+        let rectangle = rectangle(self.mode, dragging);
+        let path = Path::rectangle(rectangle.position(), rectangle.size());
+        let color = cvt_color(&appearance.series_color(0).to_backend_color());
+        frame.fill(
+            &path,
+            Color {
+                a: color.a * FILL_ALPHA,
+                ..color
+            },
+        );
+        frame.stroke(
+            &path,
+            Stroke::default().with_color(Color {
+                a: color.a * STROKE_ALPHA,
+                ..color
+            }),
+        );
+    }
+}
+
+/// selected rectangle, spanning the plotting area along axes which are not selected
+fn rectangle(mode: BrushMode, dragging: &Dragging) -> Rectangle {
+    let area = dragging.coords.plotting_area();
+    let (a, b) = (dragging.origin, dragging.current);
+    let (x, width) = match mode {
+        BrushMode::X | BrushMode::XY => (a.x.min(b.x), (a.x - b.x).abs()),
+        BrushMode::Y => (area.x, area.width),
+    };
+    let (y, height) = match mode {
+        BrushMode::Y | BrushMode::XY => (a.y.min(b.y), (a.y - b.y).abs()),
+        BrushMode::X => (area.y, area.height),
+    };
+    Rectangle::new(Point::new(x, y), Size::new(width, height))
+}
+
+/// selected ranges of data, if the selection is large enough
+fn selection(mode: BrushMode, dragging: &Dragging) -> Option<Selection> {
+    let (a, b) = (dragging.origin, dragging.current);
+    let too_small = match mode {
+        BrushMode::X => (a.x - b.x).abs() < MIN_SIZE,
+        BrushMode::Y => (a.y - b.y).abs() < MIN_SIZE,
+        BrushMode::XY => (a.x - b.x).abs() < MIN_SIZE || (a.y - b.y).abs() < MIN_SIZE,
+    };
+    if too_small {
+        return None;
+    }
+    let coords = &dragging.coords;
+    let (x0, y0) = coords.to_data(a);
+    let (x1, y1) = coords.to_data(b);
+    Some(Selection {
+        x: match mode {
+            BrushMode::X | BrushMode::XY => within(x0, x1, coords.x()),
+            BrushMode::Y => within(f64::NEG_INFINITY, f64::INFINITY, coords.x()),
+        },
+        y: match mode {
+            BrushMode::Y | BrushMode::XY => within(y0, y1, coords.y()),
+            BrushMode::X => within(f64::NEG_INFINITY, f64::INFINITY, coords.y()),
+        },
+    })
+}
+
+/// ascending range between the values, limited to the range of the axis
+fn within(a: f64, b: f64, axis: &Axis) -> Range<f64> {
+    let range = axis.range();
+    let (min, max) = (range.start.min(range.end), range.start.max(range.end));
+    a.min(b).clamp(min, max)..a.max(b).clamp(min, max)
+}
+
+fn clamp(point: Point, area: Rectangle) -> Point {
+    Point::new(
+        point.x.clamp(area.x, area.x + area.width),
+        point.y.clamp(area.y, area.y + area.height),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::tests::capture_ranges;
+
+    fn drag(brush: &mut Brush, mode: BrushMode, from: Point, to: Point) -> Option<Selection> {
+        let bounds = Rectangle::new(Point::ORIGIN, Size::new(101.0, 101.0));
+        let coords = capture_ranges(0.0..10.0, 0.0..10.0);
+        let press = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+        brush.update(mode, &press, bounds, Cursor::Available(from), &coords);
+        let moved = Event::Mouse(mouse::Event::CursorMoved { position: to });
+        brush.update(mode, &moved, bounds, Cursor::Available(to), &coords);
+        assert!(brush.is_dragging());
+        let release = Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left));
+        let (status, selection) =
+            brush.update(mode, &release, bounds, Cursor::Available(to), &coords);
+        assert_eq!(status, Status::Captured);
+        assert!(!brush.is_dragging());
+        selection
+    }
+
+    #[test]
+    fn selection_test() {
+        let mut brush = Brush::default();
+        let selection = drag(
+            &mut brush,
+            BrushMode::XY,
+            Point::new(60.0, 20.0),
+            Point::new(20.0, 60.0),
+        );
+        assert_eq!(
+            selection,
+            Some(Selection {
+                x: 2.0..6.0,
+                y: 4.0..8.0,
+            })
+        );
+
+        // the y axis is not selected, dragging beyond the plotting area is clamped
+        let selection = drag(
+            &mut brush,
+            BrushMode::X,
+            Point::new(50.0, 50.0),
+            Point::new(200.0, 50.0),
+        );
+        assert_eq!(
+            selection,
+            Some(Selection {
+                x: 5.0..10.0,
+                y: 0.0..10.0,
+            })
+        );
+
+        // clicks select nothing
+        let selection = drag(
+            &mut brush,
+            BrushMode::XY,
+            Point::new(50.0, 50.0),
+            Point::new(51.0, 50.0),
+        );
+        assert_eq!(selection, None);
+    }
+}
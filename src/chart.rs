@@ -12,9 +12,15 @@ use iced_widget::{
     canvas::{Event, Frame, Geometry},
     core::{mouse::Cursor, Size},
 };
-use plotters::{chart::ChartBuilder, coord::Shift, drawing::DrawingArea};
+use plotters::{
+    chart::ChartBuilder,
+    coord::Shift,
+    drawing::{DrawingArea, DrawingAreaErrorKind},
+};
 use plotters_backend::DrawingBackend;
 
+use crate::gesture::Gesture;
+
 /// graphics renderer
 pub trait Renderer {
     /// draw frame
@@ -38,10 +44,31 @@ where
         C::draw_chart(self, state, root);
     }
     #[inline]
+    fn try_draw_chart<DB: DrawingBackend>(
+        &self,
+        state: &Self::State,
+        root: DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        C::try_draw_chart(self, state, root)
+    }
+    #[inline]
     fn draw<R: Renderer, F: Fn(&mut Frame)>(&self, renderer: &R, size: Size, f: F) -> Geometry {
         C::draw(self, renderer, size, f)
     }
     #[inline]
+    fn layers(&self) -> &[Layer] {
+        C::layers(self)
+    }
+    #[inline]
+    fn draw_layer<DB: DrawingBackend>(
+        &self,
+        state: &Self::State,
+        layer: &Layer,
+        root: DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        C::draw_layer(self, state, layer, root)
+    }
+    #[inline]
     fn update(
         &self,
         state: &mut Self::State,
@@ -52,6 +79,29 @@ where
         C::update(self, state, event, bounds, cursor)
     }
     #[inline]
+    fn on_gesture(
+        &self,
+        state: &mut Self::State,
+        gesture: Gesture,
+        bounds: Rectangle,
+    ) -> (Status, Option<Message>) {
+        C::on_gesture(self, state, gesture, bounds)
+    }
+    #[inline]
+    fn tooltip(&self, state: &Self::State, point: (f64, f64)) -> Option<String> {
+        C::tooltip(self, state, point)
+    }
+    #[inline]
+    fn draw_overlay(
+        &self,
+        state: &Self::State,
+        frame: &mut Frame,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) {
+        C::draw_overlay(self, state, frame, bounds, cursor)
+    }
+    #[inline]
     fn mouse_interaction(
         &self,
         state: &Self::State,
@@ -62,6 +112,33 @@ where
     }
 }
 
+/// a layer of a [`Chart`] with its own cache, see [`Chart::layers`]
+#[derive(Debug, Default)]
+pub struct Layer {
+    name: &'static str,
+    pub(crate) cache: Cache,
+}
+
+impl Layer {
+    /// create a new [`Layer`]
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            cache: Cache::new(),
+        }
+    }
+
+    /// name of the layer
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// clear the cache, so that the layer is drawn again on the next frame
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
+}
+
 /// Chart View Model
 ///
 /// ## Example
@@ -120,6 +197,36 @@ pub trait Chart<Message> {
         self.build_chart(state, builder);
     }
 
+    /// fallible version of [`Chart::draw_chart`], override this method to report drawing errors
+    /// instead of panicking.
+    ///
+    /// errors are rendered on the canvas, and published by [`crate::ChartWidget::on_error`].
+    ///
+    /// ## Example
+    /// ```rust,ignore
+    /// impl Chart<Message> for MyChart {
+    ///     fn build_chart<DB: DrawingBackend>(&self, state: &Self::State, builder: ChartBuilder<DB>){}
+    ///     fn try_draw_chart<DB: DrawingBackend>(
+    ///         &self,
+    ///         state: &Self::State,
+    ///         root: DrawingArea<DB, Shift>,
+    ///     ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    ///         let mut chart = ChartBuilder::on(&root).build_cartesian_2d(0.0..1.0, 0.0..1.0)?;
+    ///         chart.configure_mesh().draw()?;
+    ///         Ok(())
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn try_draw_chart<DB: DrawingBackend>(
+        &self,
+        state: &Self::State,
+        root: DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        self.draw_chart(state, root);
+        Ok(())
+    }
+
     /// draw on [`iced_widget::canvas::Canvas`]
     ///
     /// override this method if you want to use [`iced_widget::canvas::Cache`]
@@ -141,6 +248,64 @@ pub trait Chart<Message> {
         R::draw(renderer, size, f)
     }
 
+    /// layers of the chart, drawn in order by [`Chart::draw_layer`], each with its own cache
+    ///
+    /// charts without layers are drawn by [`Chart::draw`] and [`Chart::try_draw_chart`].
+    ///
+    /// ## Example
+    /// ```rust,ignore
+    /// struct MyChart {
+    ///     // mesh and series
+    ///     layers: [Layer; 2],
+    /// }
+    ///
+    /// impl Chart<Message> for MyChart {
+    ///     fn layers(&self) -> &[Layer] {
+    ///         &self.layers
+    ///     }
+    ///
+    ///     fn draw_layer<DB: DrawingBackend>(
+    ///         &self,
+    ///         state: &Self::State,
+    ///         layer: &Layer,
+    ///         root: DrawingArea<DB, Shift>,
+    ///     ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    ///         // every layer builds the same chart, so that the layers are aligned
+    ///         let mut chart = ChartBuilder::on(&root)
+    ///             .x_label_area_size(30)
+    ///             .y_label_area_size(30)
+    ///             .build_cartesian_2d(0.0..100.0, 0.0..1.0)?;
+    ///         match layer.name() {
+    ///             "mesh" => chart.configure_mesh().draw()?,
+    ///             "series" => {
+    ///                 chart.draw_series(LineSeries::new(self.data.iter().copied(), &RED))?;
+    ///             }
+    ///             _ => {}
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// // only the series is drawn again when data arrives
+    /// self.layers[1].clear();
+    /// ```
+    #[inline]
+    fn layers(&self) -> &[Layer] {
+        &[]
+    }
+
+    /// draw a layer of the chart, see [`Chart::layers`]
+    #[inline]
+    #[allow(unused)]
+    fn draw_layer<DB: DrawingBackend>(
+        &self,
+        state: &Self::State,
+        layer: &Layer,
+        root: DrawingArea<DB, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        Ok(())
+    }
+
     /// react on event
     #[allow(unused_variables)]
     #[inline]
@@ -155,6 +320,64 @@ pub trait Chart<Message> {
         (Status::Ignored, None)
     }
 
+    /// react on pinch and pan gestures made by two fingers within the chart
+    ///
+    /// the touch events making up the gesture are passed to [`Chart::update`] before.
+    #[inline]
+    #[allow(unused)]
+    fn on_gesture(
+        &self,
+        state: &mut Self::State,
+        gesture: Gesture,
+        bounds: Rectangle,
+    ) -> (Status, Option<Message>) {
+        (Status::Ignored, None)
+    }
+
+    /// draw cursor dependent content above the chart, e.g. crosshairs or markers of hovered points
+    ///
+    /// the overlay is drawn on every frame, without clearing the cache of [`Chart::draw`].
+    /// coordinates captured by the chart are available, see [`crate::coords`].
+    ///
+    /// ## Example
+    /// ```rust,ignore
+    /// fn draw_overlay(&self, state: &Self::State, frame: &mut Frame, bounds: Rectangle, cursor: Cursor) {
+    ///     if let Some(position) = cursor.position_in(bounds) {
+    ///         frame.fill(&Path::circle(position, 5.0), Color::from_rgb8(0xff, 0xcc, 0x00));
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    #[allow(unused)]
+    fn draw_overlay(
+        &self,
+        state: &Self::State,
+        frame: &mut Frame,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) {
+        // no layer is opened for the overlay
+        crate::renderer::skip_overlay();
+    }
+
+    /// content of the tooltip shown near the cursor, which hovers the data point `(x, y)`
+    ///
+    /// the data point is mapped by the coordinates captured by
+    /// [`crate::coords::CaptureCoords::capture_coords`], no tooltip is shown if none is captured.
+    ///
+    /// ## Example
+    /// ```rust,ignore
+    /// fn tooltip(&self, state: &Self::State, (x, _): (f64, f64)) -> Option<String> {
+    ///     let (px, py) = self.nearest_point(x)?;
+    ///     Some(format!("x: {px:.2}\ny: {py:.2}"))
+    /// }
+    /// ```
+    #[inline]
+    #[allow(unused)]
+    fn tooltip(&self, state: &Self::State, point: (f64, f64)) -> Option<String> {
+        None
+    }
+
     /// Returns the current mouse interaction of the [`Chart`]
     #[inline]
     #[allow(unused)]
@@ -0,0 +1,412 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Mapping between pixels and data
+//!
+//! The coordinate spec of a chart is lost once [`crate::Chart::build_chart`] returns. Charts
+//! which call [`CaptureCoords::capture_coords`] while drawing get the mapping of their plotting
+//! areas back in [`crate::Chart::update`] and [`crate::Chart::mouse_interaction`], through
+//! [`captured`] and [`under_cursor`].
+//!
+//! The widget maps pixels to data with the same coordinates, e.g. for
+//! [`crate::ChartWidget::zoomable`] and [`crate::ChartWidget::on_select`], so charts using them
+//! must capture their coordinates.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::coords::{self, CaptureCoords};
+//!
+//! fn build_chart<DB: DrawingBackend>(&self, state: &Self::State, mut builder: ChartBuilder<DB>) {
+//!     let mut chart = builder.build_cartesian_2d(0.0..10.0, 0.0..100.0).unwrap();
+//!     chart.capture_coords();
+//!     //...
+//! }
+//!
+//! fn update(&self, state: &mut Self::State, event: Event, bounds: Rectangle, cursor: Cursor)
+//!     -> (Status, Option<Message>) {
+//!     if let Some((x, y)) = coords::under_cursor(bounds, cursor)
+//!         .and_then(|coords| coords.data_at(bounds, cursor))
+//!     {
+//!         return (Status::Ignored, Some(Message::Hover(x, y)));
+//!     }
+//!     (Status::Ignored, None)
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::Rc;
+
+use iced_widget::core::{mouse::Cursor, Point, Rectangle, Size};
+use plotters::{
+    chart::ChartContext,
+    coord::{
+        combinators::LogCoord,
+        ranged1d::Ranged,
+        types::{RangedCoordf32, RangedCoordf64, RangedCoordi32, RangedCoordi64, RangedCoordu32},
+    },
+    prelude::Cartesian2d,
+};
+use plotters_backend::DrawingBackend;
+
+use crate::precise::{map_linear, pixel_ends};
+use crate::utils::scoped;
+
+thread_local! {
+    /// coordinates captured by the drawing in progress
+    static CAPTURING: RefCell<Option<Vec<Coords>>> = const { RefCell::new(None) };
+    /// coordinates captured by the last drawing, while the chart handles events
+    static CURRENT: RefCell<Option<Rc<[Coords]>>> = const { RefCell::new(None) };
+}
+
+/// how data values are mapped to pixels along an axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    /// linear mapping
+    Linear,
+    /// logarithmic mapping, for positive ranges
+    Log,
+}
+
+/// mapping of an axis between data values and pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    /// data values at the ends of the axis
+    values: (f64, f64),
+    /// pixels of the ends of the axis, relative to the chart
+    pixels: (f64, f64),
+    scale: Scale,
+}
+
+impl Axis {
+    /// range of data values of the axis
+    pub fn range(&self) -> Range<f64> {
+        self.values.0..self.values.1
+    }
+
+    /// scale of the axis
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
+
+    /// data value at the pixel, relative to the chart
+    pub fn value_at(&self, pixel: f32) -> f64 {
+        self.value_at_precise(pixel as f64)
+    }
+
+    fn value_at_precise(&self, pixel: f64) -> f64 {
+        if self.pixels.0 == self.pixels.1 {
+            return self.values.0;
+        }
+        let value = map_linear(pixel, self.pixels, self.scaled());
+        match self.scale {
+            Scale::Linear => value,
+            Scale::Log => value.exp(),
+        }
+    }
+
+    /// pixel of the data value, relative to the chart
+    pub fn pixel_of(&self, value: f64) -> f32 {
+        let scaled = self.scaled();
+        if scaled.0 == scaled.1 {
+            return self.pixels.0 as f32;
+        }
+        let value = match self.scale {
+            Scale::Linear => value,
+            Scale::Log => value.ln(),
+        };
+        map_linear(value, scaled, self.pixels) as f32
+    }
+
+    /// range of data values after zooming by `factor` around the pixel, zooms in if `factor` is
+    /// less than 1
+    pub fn zoomed(&self, pixel: f32, factor: f32) -> Range<f64> {
+        let (p0, p1) = self.pixels;
+        let (center, factor) = (pixel as f64, factor as f64);
+        let start = self.value_at_precise(center + (p0 - center) * factor);
+        let end = self.value_at_precise(center + (p1 - center) * factor);
+        start..end
+    }
+
+    /// range of data values after moving the content by `delta` pixels
+    pub fn panned(&self, delta: f32) -> Range<f64> {
+        let (p0, p1) = self.pixels;
+        let start = self.value_at_precise(p0 - delta as f64);
+        let end = self.value_at_precise(p1 - delta as f64);
+        start..end
+    }
+
+    /// ends of the axis in linear space
+    fn scaled(&self) -> (f64, f64) {
+        match self.scale {
+            Scale::Linear => self.values,
+            Scale::Log => (self.values.0.ln(), self.values.1.ln()),
+        }
+    }
+}
+
+/// mapping of a plotting area between data values and pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coords {
+    plotting_area: Rectangle,
+    x: Axis,
+    y: Axis,
+}
+
+impl Coords {
+    /// plotting area, relative to the chart
+    pub fn plotting_area(&self) -> Rectangle {
+        self.plotting_area
+    }
+
+    /// the x axis
+    pub fn x(&self) -> &Axis {
+        &self.x
+    }
+
+    /// the y axis
+    pub fn y(&self) -> &Axis {
+        &self.y
+    }
+
+    /// data values at the point, relative to the chart
+    pub fn to_data(&self, point: Point) -> (f64, f64) {
+        (self.x.value_at(point.x), self.y.value_at(point.y))
+    }
+
+    /// point of the data values, relative to the chart
+    pub fn from_data(&self, (x, y): (f64, f64)) -> Point {
+        Point::new(self.x.pixel_of(x), self.y.pixel_of(y))
+    }
+
+    /// the same mapping of pixels, to other ranges of data values
+    pub(crate) fn with_ranges(&self, x: &Range<f64>, y: &Range<f64>) -> Coords {
+        Coords {
+            plotting_area: self.plotting_area,
+            x: Axis {
+                values: (x.start, x.end),
+                ..self.x.clone()
+            },
+            y: Axis {
+                values: (y.start, y.end),
+                ..self.y.clone()
+            },
+        }
+    }
+
+    /// data values under the cursor, if it is over the plotting area
+    pub fn data_at(&self, bounds: Rectangle, cursor: Cursor) -> Option<(f64, f64)> {
+        let position = cursor.position_in(bounds)?;
+        self.plotting_area
+            .contains(position)
+            .then(|| self.to_data(position))
+    }
+}
+
+/// ranged coordinates whose mapping can be captured
+pub trait CoordsRanged: Ranged {
+    /// data values at the ends of the range
+    fn values(&self) -> (f64, f64);
+
+    /// scale of the mapping
+    fn scale(&self) -> Scale {
+        Scale::Linear
+    }
+}
+
+macro_rules! impl_coords_ranged {
+    ($($ranged:ty),*) => {
+        $(
+            impl CoordsRanged for $ranged {
+                fn values(&self) -> (f64, f64) {
+                    let range = self.range();
+                    (range.start as f64, range.end as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_coords_ranged!(
+    RangedCoordf32,
+    RangedCoordf64,
+    RangedCoordi32,
+    RangedCoordi64,
+    RangedCoordu32
+);
+
+impl CoordsRanged for LogCoord<f32> {
+    fn values(&self) -> (f64, f64) {
+        let range = self.range();
+        (range.start as f64, range.end as f64)
+    }
+
+    fn scale(&self) -> Scale {
+        Scale::Log
+    }
+}
+
+impl CoordsRanged for LogCoord<f64> {
+    fn values(&self) -> (f64, f64) {
+        let range = self.range();
+        (range.start, range.end)
+    }
+
+    fn scale(&self) -> Scale {
+        Scale::Log
+    }
+}
+
+/// charts whose mapping between pixels and data can be captured
+pub trait CaptureCoords {
+    /// captures the mapping of the plotting area, when drawn by [`crate::ChartWidget`]
+    fn capture_coords(&self);
+}
+
+impl<DB, X, Y> CaptureCoords for ChartContext<'_, DB, Cartesian2d<X, Y>>
+where
+    DB: DrawingBackend,
+    X: CoordsRanged,
+    Y: CoordsRanged,
+{
+    fn capture_coords(&self) {
+        CAPTURING.with(|capturing| {
+            let mut capturing = capturing.borrow_mut();
+            let Some(captured) = capturing.as_mut() else {
+                return;
+            };
+            let spec = self.as_coord_spec();
+            let (x_ends, y_ends) = pixel_ends(spec);
+            let (x_pixels, y_pixels) = self.plotting_area().get_pixel_range();
+            captured.push(Coords {
+                plotting_area: Rectangle::new(
+                    Point::new(x_pixels.start as f32, y_pixels.start as f32),
+                    Size::new(
+                        (x_pixels.end - x_pixels.start) as f32,
+                        (y_pixels.end - y_pixels.start) as f32,
+                    ),
+                ),
+                x: Axis {
+                    values: spec.x_spec().values(),
+                    pixels: (x_ends.0 as f64, x_ends.1 as f64),
+                    scale: spec.x_spec().scale(),
+                },
+                y: Axis {
+                    values: spec.y_spec().values(),
+                    pixels: (y_ends.0 as f64, y_ends.1 as f64),
+                    scale: spec.y_spec().scale(),
+                },
+            });
+        });
+    }
+}
+
+/// coordinates of the plotting areas captured by the last drawing of the chart
+///
+/// only available in [`crate::Chart::update`] and [`crate::Chart::mouse_interaction`]; the
+/// coordinates are shared with the widget, not copied.
+pub fn captured() -> Rc<[Coords]> {
+    CURRENT.with(|current| current.borrow().clone().unwrap_or_else(|| Rc::new([])))
+}
+
+/// coordinates of the plotting area under the cursor
+///
+/// only available in [`crate::Chart::update`] and [`crate::Chart::mouse_interaction`].
+pub fn under_cursor(bounds: Rectangle, cursor: Cursor) -> Option<Coords> {
+    let position = cursor.position_in(bounds)?;
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .iter()
+            .flat_map(|captured| captured.iter())
+            .find(|coords| coords.plotting_area.contains(position))
+            .cloned()
+    })
+}
+
+/// calls `f`, returns the coordinates captured by it
+pub(crate) fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<Coords>) {
+    let (result, captured) = scoped(&CAPTURING, Some(Vec::new()), f);
+    (result, captured.unwrap_or_default())
+}
+
+/// calls `f` with the coordinates captured by the last drawing
+pub(crate) fn with_captured<R>(coords: Rc<[Coords]>, f: impl FnOnce() -> R) -> R {
+    scoped(&CURRENT, Some(coords), f).0
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use plotters::{
+        chart::ChartBuilder, coord::combinators::IntoLogRange, drawing::IntoDrawingArea,
+        prelude::SVGBackend,
+    };
+
+    use super::*;
+
+    /// coordinates of a chart mapping the ranges to pixels 0 to 100, shared by the tests of
+    /// the widget features
+    pub(crate) fn capture_ranges(x: Range<f64>, y: Range<f64>) -> Vec<Coords> {
+        let mut svg = String::new();
+        capture(|| {
+            let root = SVGBackend::with_string(&mut svg, (101, 101)).into_drawing_area();
+            let chart = ChartBuilder::on(&root).build_cartesian_2d(x, y).unwrap();
+            chart.capture_coords();
+        })
+        .1
+    }
+
+    #[test]
+    fn axis_test() {
+        let linear = Axis {
+            values: (0.0, 10.0),
+            pixels: (100.0, 200.0),
+            scale: Scale::Linear,
+        };
+        assert_eq!(linear.value_at(125.0), 2.5);
+        assert_eq!(linear.pixel_of(2.5), 125.0);
+
+        // y axis grows upwards
+        let log = Axis {
+            values: (1.0, 1000.0),
+            pixels: (300.0, 0.0),
+            scale: Scale::Log,
+        };
+        assert!((log.value_at(200.0) - 10.0).abs() < 1e-9);
+        assert!((log.pixel_of(100.0) - 100.0).abs() < 1e-3);
+
+        assert_eq!(linear.zoomed(125.0, 0.5), 1.25..6.25);
+        assert_eq!(linear.panned(10.0), -1.0..9.0);
+        let zoomed = log.zoomed(150.0, 0.5);
+        assert!((zoomed.start.log10() - 0.75).abs() < 1e-9);
+        assert!((zoomed.end.log10() - 2.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn capture_test() {
+        let mut svg = String::new();
+        let ((), captured) = capture(|| {
+            let root = SVGBackend::with_string(&mut svg, (400, 300)).into_drawing_area();
+            let chart = ChartBuilder::on(&root)
+                .build_cartesian_2d(0.0..10.0, (1.0..1000.0).log_scale())
+                .unwrap();
+            chart.capture_coords();
+        });
+        assert_eq!(captured.len(), 1);
+        let coords = &captured[0];
+        assert_eq!(coords.plotting_area().size(), Size::new(400.0, 300.0));
+        assert_eq!(coords.y().scale(), Scale::Log);
+        let (x, y) = coords.to_data(Point::new(200.0, 150.0));
+        assert!((x - 5.0).abs() < 0.1);
+        assert!((y.log10() - 1.5).abs() < 0.1);
+
+        // nothing is captured out of drawings
+        with_captured(captured.clone().into(), || {
+            assert_eq!(*self::captured(), *captured)
+        });
+        assert!(self::captured().is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! crosshair at the cursor, with badges of the data values under it
+//!
+//! lines take the axis color of the [`Appearance`], badges invert its text and background colors.
+
+use iced_widget::{
+    canvas::{Frame, LineDash, Path, Stroke, Text},
+    core::{
+        alignment::{Horizontal, Vertical},
+        text::Paragraph,
+        Color, Font, Pixels, Point, Size, Vector,
+    },
+    text::Shaping,
+};
+
+use plotters::style::Color as _;
+
+use crate::coords::{Axis, Coords, Scale};
+use crate::style::Appearance;
+use crate::utils::cvt_color;
+
+const BADGE_TEXT_SIZE: f32 = 12.0;
+const BADGE_PADDING: f32 = 3.0;
+
+/// draws the crosshair, if the position is over a plotting area
+pub(crate) fn draw<P: Paragraph<Font = Font>>(
+    frame: &mut Frame,
+    coords: &[Coords],
+    position: Point,
+    shaping: Shaping,
+    appearance: &Appearance,
+) {
+    let Some(coords) = coords
+        .iter()
+        .find(|coords| coords.plotting_area().contains(position))
+    else {
+        return;
+    };
+    let area = coords.plotting_area();
+    let lines = Path::new(|builder| {
+        builder.move_to(Point::new(position.x, area.y));
+        builder.line_to(Point::new(position.x, area.y + area.height));
+        builder.move_to(Point::new(area.x, position.y));
+        builder.line_to(Point::new(area.x + area.width, position.y));
+    });
+    frame.stroke(
+        &lines,
+        Stroke {
+            line_dash: LineDash {
+                segments: &[4.0, 4.0],
+                offset: 0,
+            },
+            ..Stroke::default()
+                .with_color(cvt_color(&appearance.axis.to_backend_color()))
+                .with_width(1.0)
+        },
+    );
+
+    let colors = (
+        Color {
+            a: 0.85,
+            ..cvt_color(&appearance.text.to_backend_color())
+        },
+        cvt_color(&appearance.background.to_backend_color()),
+    );
+    let (x, y) = coords.to_data(position);
+    // the x value below the plotting area, the y value left of it
+    badge::<P>(
+        frame,
+        format_value(x, coords.x()),
+        Point::new(position.x, area.y + area.height),
+        (Horizontal::Center, Vertical::Top),
+        colors,
+        shaping,
+    );
+    badge::<P>(
+        frame,
+        format_value(y, coords.y()),
+        Point::new(area.x, position.y),
+        (Horizontal::Right, Vertical::Center),
+        colors,
+        shaping,
+    );
+}
+
+/// draws the text on a background, aligned to the anchor and kept within the frame
+fn badge<P: Paragraph<Font = Font>>(
+    frame: &mut Frame,
+    content: String,
+    anchor: Point,
+    (horizontal, vertical): (Horizontal, Vertical),
+    (background, color): (Color, Color),
+    shaping: Shaping,
+) {
+    let mut paragraph = P::default();
+    paragraph.update(iced_widget::core::text::Text {
+        content: &content,
+        bounds: Size::INFINITY,
+        size: Pixels(BADGE_TEXT_SIZE),
+        line_height: Default::default(),
+        font: Font::DEFAULT,
+        horizontal_alignment: Horizontal::Left,
+        vertical_alignment: Vertical::Top,
+        shaping,
+    });
+    let size = paragraph
+        .min_bounds()
+        .expand(Size::new(BADGE_PADDING * 2.0, BADGE_PADDING * 2.0));
+    let x = match horizontal {
+        Horizontal::Left => anchor.x,
+        Horizontal::Center => anchor.x - size.width / 2.0,
+        Horizontal::Right => anchor.x - size.width,
+    };
+    let y = match vertical {
+        Vertical::Top => anchor.y,
+        Vertical::Center => anchor.y - size.height / 2.0,
+        Vertical::Bottom => anchor.y - size.height,
+    };
+    let frame_size = frame.size();
+    let position = Point::new(
+        x.min(frame_size.width - size.width).max(0.0),
+        y.min(frame_size.height - size.height).max(0.0),
+    );
+    frame.fill_rectangle(position, size, background);
+    frame.fill_text(Text {
+        content,
+        position: position + Vector::new(BADGE_PADDING, BADGE_PADDING),
+        color,
+        size: Pixels(BADGE_TEXT_SIZE),
+        shaping,
+        ..Default::default()
+    });
+}
+
+/// formats the value with as many decimals as distinguish values along the axis
+fn format_value(value: f64, axis: &Axis) -> String {
+    let range = axis.range();
+    let span = match axis.scale() {
+        Scale::Linear => (range.end - range.start).abs(),
+        Scale::Log => value.abs(),
+    };
+    let decimals = decimals(span);
+    format!("{value:.decimals$}")
+}
+
+/// decimals of values within the span
+fn decimals(span: f64) -> usize {
+    if span > 0.0 && span.is_finite() {
+        (3.0 - span.log10().floor()).clamp(0.0, 12.0) as usize
+    } else {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_test() {
+        assert_eq!(decimals(10.0), 2);
+        assert_eq!(decimals(0.5), 4);
+        assert_eq!(decimals(25000.0), 0);
+        assert_eq!(decimals(0.0), 3);
+    }
+}
@@ -7,14 +7,97 @@
 use std::error::Error as StdError;
 use std::fmt;
 
-#[derive(Debug)]
+use plotters::drawing::DrawingAreaErrorKind;
+use plotters_backend::DrawingErrorKind;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 /// Indicates that some error occurred within the Iced backend
-pub enum Error {}
+pub enum Error {
+    /// the buffer of a bitmap is neither RGB nor RGBA of its dimensions
+    InvalidBitmap {
+        /// width of the bitmap
+        width: u32,
+        /// height of the bitmap
+        height: u32,
+        /// length of the buffer
+        len: usize,
+    },
+    /// the bitmap exceeds the maximum dimensions of images
+    BitmapTooLarge {
+        /// width of the bitmap
+        width: u32,
+        /// height of the bitmap
+        height: u32,
+    },
+    /// coordinates of a shape are not finite
+    InvalidGeometry,
+    /// font error reported by plotters
+    Font(String),
+    /// the drawing area is used by another drawing operation
+    Sharing,
+    /// invalid layout of the drawing area
+    Layout,
+    /// drawing backend of an exported chart failed
+    Export(String),
+    /// the clipboard of the system is unavailable or rejected the image
+    Clipboard(String),
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{self:?}")
+        match self {
+            Error::InvalidBitmap { width, height, len } => write!(
+                fmt,
+                "invalid bitmap buffer of {len} bytes for {width}x{height} pixels"
+            ),
+            Error::BitmapTooLarge { width, height } => {
+                write!(fmt, "bitmap of {width}x{height} pixels is too large")
+            }
+            Error::InvalidGeometry => write!(fmt, "coordinates are not finite"),
+            Error::Font(e) => write!(fmt, "font error: {e}"),
+            Error::Sharing => write!(fmt, "drawing area is used by another drawing"),
+            Error::Layout => write!(fmt, "invalid layout of drawing area"),
+            Error::Export(e) => write!(fmt, "export error: {e}"),
+            Error::Clipboard(e) => write!(fmt, "clipboard error: {e}"),
+        }
     }
 }
 
 impl StdError for Error {}
+
+impl From<DrawingErrorKind<Error>> for Error {
+    fn from(e: DrawingErrorKind<Error>) -> Self {
+        match e {
+            DrawingErrorKind::DrawingError(e) => e,
+            DrawingErrorKind::FontError(e) => Error::Font(e.to_string()),
+        }
+    }
+}
+
+impl From<DrawingAreaErrorKind<Error>> for Error {
+    fn from(e: DrawingAreaErrorKind<Error>) -> Self {
+        match e {
+            DrawingAreaErrorKind::BackendError(e) => e.into(),
+            DrawingAreaErrorKind::SharingError => Error::Sharing,
+            DrawingAreaErrorKind::LayoutError => Error::Layout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_drawing_area_error_test() {
+        let e = DrawingAreaErrorKind::BackendError(DrawingErrorKind::DrawingError(
+            Error::InvalidGeometry,
+        ));
+        assert_eq!(Error::from(e), Error::InvalidGeometry);
+        assert_eq!(
+            Error::from(DrawingAreaErrorKind::<Error>::LayoutError),
+            Error::Layout
+        );
+    }
+}
@@ -0,0 +1,346 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Headless rendering
+//!
+//! renders a [`Chart`] with its state outside of iced, e.g. for reports, so exports and the UI
+//! share one chart definition. [`to_svg`] requires the `svg` feature, [`to_png`] and
+//! [`to_image`] require the `png` feature, and [`to_clipboard`] the `clipboard` feature.
+//!
+//! charts are drawn on the background of the appearance, which is also returned by
+//! [`crate::style::appearance`] while drawing. Layers are drawn in order, and charts are neither
+//! zoomed nor panned.
+//!
+//! texts of PNGs are drawn by the `ab_glyph` font backend of plotters, which only knows
+//! registered fonts. The bundled Noto Sans is registered for `"sans-serif"`, the default family of
+//! plotters; register other families, including `"serif"` and `"monospace"`, with
+//! [`register_font`], otherwise drawing their texts fails with [`Error::Font`].
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::{export, style::Appearance};
+//!
+//! let svg = export::to_svg(&chart, &state, (800, 600), &Appearance::default())?;
+//! ```
+
+use plotters::{
+    coord::Shift,
+    drawing::{DrawingArea, DrawingAreaErrorKind, IntoDrawingArea},
+};
+use plotters_backend::{DrawingBackend, DrawingErrorKind};
+
+use crate::style::{self, Appearance};
+use crate::{Chart, Error};
+
+#[cfg(all(feature = "png", not(target_arch = "wasm32")))]
+static NOTO_SANS: &[u8] = include_bytes!("../assets/fonts/notosans-regular.ttf");
+#[cfg(all(feature = "png", not(target_arch = "wasm32")))]
+static NOTO_SANS_BOLD: &[u8] = include_bytes!("../assets/fonts/notosans-bold.ttf");
+
+/// registers the font data of a plotters font family and style for texts of PNGs, e.g.
+/// `register_font("Arial", FontStyle::Normal, include_bytes!("arial.ttf"))`.
+///
+/// Styles which are not registered fall back to [`FontStyle::Normal`](plotters::style::FontStyle),
+/// and registered fonts of `"sans-serif"` replace the bundled fonts.
+#[cfg(all(feature = "png", not(target_arch = "wasm32")))]
+pub fn register_font(
+    name: &str,
+    style: plotters::style::FontStyle,
+    bytes: &'static [u8],
+) -> Result<(), Error> {
+    register_bundled_fonts();
+    plotters::style::register_font(name, style, bytes)
+        .map_err(|_| Error::Font(format!("invalid font data of {name}")))
+}
+
+/// registers the bundled fonts for `"sans-serif"` once
+#[cfg(all(feature = "png", not(target_arch = "wasm32")))]
+fn register_bundled_fonts() {
+    use plotters::style::FontStyle;
+    static REGISTERED: std::sync::Once = std::sync::Once::new();
+    REGISTERED.call_once(|| {
+        for (style, bytes) in [
+            (FontStyle::Normal, NOTO_SANS),
+            (FontStyle::Bold, NOTO_SANS_BOLD),
+        ] {
+            plotters::style::register_font("sans-serif", style, bytes)
+                .unwrap_or_else(|_| panic!("invalid bundled font"));
+        }
+    });
+}
+
+/// renders the chart to an SVG document of `size` pixels
+#[cfg(feature = "svg")]
+pub fn to_svg<Message, C>(
+    chart: &C,
+    state: &C::State,
+    size: (u32, u32),
+    appearance: &Appearance,
+) -> Result<String, Error>
+where
+    C: Chart<Message>,
+{
+    let mut svg = String::new();
+    {
+        let root = plotters::backend::SVGBackend::with_string(&mut svg, size).into_drawing_area();
+        draw(chart, state, &root, appearance)?;
+    }
+    Ok(svg)
+}
+
+/// pixels of a rendered chart, e.g. for clipboards
+#[cfg(feature = "png")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    /// width in pixels
+    pub width: u32,
+    /// height in pixels
+    pub height: u32,
+    /// RGBA pixels, row by row
+    pub pixels: Vec<u8>,
+}
+
+/// renders the chart to a PNG image of `size` pixels
+#[cfg(feature = "png")]
+pub fn to_png<Message, C>(
+    chart: &C,
+    state: &C::State,
+    size: (u32, u32),
+    appearance: &Appearance,
+) -> Result<Vec<u8>, Error>
+where
+    C: Chart<Message>,
+{
+    let (width, height) = size;
+    let rgb = to_rgb(chart, state, size, appearance)?;
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&rgb))
+        .map_err(|e| Error::Export(e.to_string()))?;
+    Ok(png)
+}
+
+/// renders the chart to an [`Image`] of `size` pixels
+#[cfg(feature = "png")]
+pub fn to_image<Message, C>(
+    chart: &C,
+    state: &C::State,
+    size: (u32, u32),
+    appearance: &Appearance,
+) -> Result<Image, Error>
+where
+    C: Chart<Message>,
+{
+    let rgb = to_rgb(chart, state, size, appearance)?;
+    let pixels = rgb
+        .chunks(3)
+        .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+        .collect();
+    Ok(Image {
+        width: size.0,
+        height: size.1,
+        pixels,
+    })
+}
+
+/// writes the image to the clipboard of the system, requires the `clipboard` feature
+///
+/// the clipboard is kept open by the calling thread, since some platforms, e.g. X11, only serve
+/// the contents of the clipboard while it is open.
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+pub fn to_clipboard(image: &Image) -> Result<(), Error> {
+    use std::borrow::Cow;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+    }
+
+    let cvt_error = |e: arboard::Error| Error::Clipboard(e.to_string());
+    CLIPBOARD.with(|clipboard| {
+        let mut clipboard = clipboard.borrow_mut();
+        let clipboard = match clipboard.as_mut() {
+            Some(clipboard) => clipboard,
+            None => clipboard.insert(arboard::Clipboard::new().map_err(cvt_error)?),
+        };
+        clipboard
+            .set_image(arboard::ImageData {
+                width: image.width as usize,
+                height: image.height as usize,
+                bytes: Cow::Borrowed(&image.pixels),
+            })
+            .map_err(cvt_error)
+    })
+}
+
+#[cfg(feature = "png")]
+fn to_rgb<Message, C>(
+    chart: &C,
+    state: &C::State,
+    size: (u32, u32),
+    appearance: &Appearance,
+) -> Result<Vec<u8>, Error>
+where
+    C: Chart<Message>,
+{
+    let (width, height) = size;
+    let mut rgb = vec![0; width as usize * height as usize * 3];
+    {
+        let root =
+            plotters::backend::BitMapBackend::with_buffer(&mut rgb, size).into_drawing_area();
+        draw(chart, state, &root, appearance)?;
+    }
+    Ok(rgb)
+}
+
+/// draws the background and the chart, or its layers
+fn draw<Message, C, DB>(
+    chart: &C,
+    state: &C::State,
+    root: &DrawingArea<DB, Shift>,
+    appearance: &Appearance,
+) -> Result<(), Error>
+where
+    C: Chart<Message>,
+    DB: DrawingBackend,
+{
+    #[cfg(all(feature = "png", not(target_arch = "wasm32")))]
+    register_bundled_fonts();
+    style::with_appearance(appearance.clone(), || {
+        root.fill(&appearance.background)?;
+        let layers = chart.layers();
+        if layers.is_empty() {
+            chart.try_draw_chart(state, root.clone())?;
+        } else {
+            for layer in layers {
+                chart.draw_layer(state, layer, root.clone())?;
+            }
+        }
+        root.present()
+    })
+    .map_err(cvt_error)
+}
+
+fn cvt_error<E: std::error::Error + Send + Sync>(e: DrawingAreaErrorKind<E>) -> Error {
+    match e {
+        DrawingAreaErrorKind::BackendError(DrawingErrorKind::DrawingError(e)) => {
+            Error::Export(e.to_string())
+        }
+        DrawingAreaErrorKind::BackendError(DrawingErrorKind::FontError(e)) => {
+            Error::Font(e.to_string())
+        }
+        DrawingAreaErrorKind::SharingError => Error::Sharing,
+        DrawingAreaErrorKind::LayoutError => Error::Layout,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plotters::prelude::*;
+
+    use super::*;
+
+    struct Lines;
+
+    impl Chart<()> for Lines {
+        type State = ();
+
+        fn build_chart<DB: DrawingBackend>(
+            &self,
+            _state: &Self::State,
+            mut builder: ChartBuilder<DB>,
+        ) {
+            let appearance = style::appearance();
+            let mut chart = builder.build_cartesian_2d(0.0..1.0, 0.0..1.0).unwrap();
+            chart
+                .draw_series(LineSeries::new(
+                    [(0.0, 0.0), (1.0, 1.0)],
+                    appearance.series_color(0),
+                ))
+                .unwrap();
+        }
+    }
+
+    struct Labels;
+
+    impl Chart<()> for Labels {
+        type State = ();
+
+        fn build_chart<DB: DrawingBackend>(
+            &self,
+            _state: &Self::State,
+            mut builder: ChartBuilder<DB>,
+        ) {
+            let mut chart = builder
+                .caption("Labels", ("sans-serif", 20))
+                .x_label_area_size(20)
+                .y_label_area_size(30)
+                .build_cartesian_2d(0.0..1.0, 0.0..1.0)
+                .unwrap();
+            chart
+                .configure_mesh()
+                .disable_mesh()
+                .x_desc("x")
+                .y_desc("y")
+                .draw()
+                .unwrap();
+        }
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn svg_test() {
+        let appearance = Appearance::default();
+        let svg = to_svg(&Lines, &(), (40, 30), &appearance).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"width="40" height="30""#));
+        assert!(svg.contains("polyline"));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png_test() {
+        let appearance = Appearance::default();
+        let png = to_png(&Lines, &(), (40, 30), &appearance).unwrap();
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut rgb = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut rgb).unwrap();
+        assert_eq!((info.width, info.height), (40, 30));
+        // white background, the red line from the bottom left to the top right
+        assert_eq!(&rgb[..3], &[255, 255, 255]);
+        // the line is antialiased
+        let red = rgb
+            .chunks(3)
+            .filter(|pixel| pixel[0] == 255 && pixel[1] < 128)
+            .count();
+        assert!(red >= 30, "{red} red pixels");
+
+        let image = to_image(&Lines, &(), (40, 30), &appearance).unwrap();
+        assert_eq!(image.pixels.len(), 40 * 30 * 4);
+        assert_eq!(&image.pixels[..4], &[255, 255, 255, 255]);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png_text_test() {
+        let appearance = Appearance::default();
+        let image = to_image(&Labels, &(), (200, 150), &appearance).unwrap();
+        // the caption is drawn in the top rows, above the plotting area
+        let caption = image.pixels[..200 * 20 * 4]
+            .chunks(4)
+            .filter(|pixel| pixel[0] < 128)
+            .count();
+        assert!(caption > 0, "no caption pixels");
+
+        let png = to_png(&Labels, &(), (200, 150), &appearance).unwrap();
+        assert!(!png.is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Font registry
+//!
+//! Maps plotters font families to iced [`Font`]s. The registry is global and thread safe, so it
+//! can be shared by charts of multiple windows.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use iced::Font;
+//! use plotters_iced::fonts;
+//!
+//! // draw texts of ("sans-serif", 15) with the bundled font
+//! fonts::register("sans-serif", Font::with_name("Noto Sans"));
+//! // an alias of a font family
+//! fonts::register("Helvetica", Font::with_name("Noto Sans"));
+//! ```
+//!
+//! For font families shipping separate faces per style, override the mapping of a chart with
+//! [`ChartWidget::font_resolver`](crate::ChartWidget::font_resolver).
+
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+use iced_widget::core::{font, Font};
+use once_cell::sync::Lazy;
+use plotters_backend::{FontFamily, FontStyle};
+
+/// interned family names
+static NAMES: Lazy<RwLock<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+/// registered fonts, by plotters family names
+static FONTS: Lazy<RwLock<HashMap<String, Font>>> = Lazy::new(Default::default);
+
+/// interns a font family name.
+///
+/// iced requires family names to be `'static`; every distinct name is allocated only once and
+/// lives until the program exits.
+pub fn intern(name: &str) -> &'static str {
+    if let Some(name) = NAMES.read().expect("read font names").get(name) {
+        return name;
+    }
+    let mut names = NAMES.write().expect("write font names");
+    // might be interned by another thread in the meantime
+    if let Some(name) = names.get(name) {
+        return name;
+    }
+    let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+    names.insert(name);
+    name
+}
+
+/// registers the iced [`Font`] for a plotters font family name, e.g. `"sans-serif"` or `"Arial"`.
+///
+/// Returns the previously registered [`Font`] of the name.
+pub fn register(name: impl Into<String>, font: Font) -> Option<Font> {
+    FONTS
+        .write()
+        .expect("write fonts")
+        .insert(name.into(), font)
+}
+
+/// removes the registered [`Font`] of a plotters font family name
+pub fn unregister(name: &str) -> Option<Font> {
+    FONTS.write().expect("write fonts").remove(name)
+}
+
+/// returns the registered [`Font`] of a plotters font family name
+pub fn get(name: &str) -> Option<Font> {
+    FONTS.read().expect("read fonts").get(name).copied()
+}
+
+/// resolves a plotters font family into an iced [`Font`].
+///
+/// Registered fonts take precedence; otherwise generic families map to iced generic families,
+/// and other names map to iced fonts of the same (interned) name.
+pub fn resolve(family: FontFamily) -> Font {
+    if let Some(font) = get(family.as_str()) {
+        return font;
+    }
+    let family = match family {
+        FontFamily::Serif => font::Family::Serif,
+        FontFamily::SansSerif => font::Family::SansSerif,
+        FontFamily::Monospace => font::Family::Monospace,
+        FontFamily::Name(name) => font::Family::Name(intern(name)),
+    };
+    Font {
+        family,
+        ..Font::DEFAULT
+    }
+}
+
+/// resolves a plotters font family and style into an iced [`Font`].
+///
+/// The style is applied on top of the font resolved by [`resolve`].
+pub fn resolve_style(family: FontFamily<'_>, style: FontStyle) -> Font {
+    let font = resolve(family);
+    match style {
+        FontStyle::Normal => font,
+        FontStyle::Oblique => Font {
+            style: font::Style::Oblique,
+            ..font
+        },
+        FontStyle::Italic => Font {
+            style: font::Style::Italic,
+            ..font
+        },
+        FontStyle::Bold => Font {
+            weight: font::Weight::Bold,
+            ..font
+        },
+    }
+}
+
+/// the function mapping plotters font families and styles to iced [`Font`]s
+pub(crate) type FontResolver<'a> = dyn Fn(FontFamily<'_>, FontStyle) -> Font + 'a;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_test() {
+        let a = intern("plotters-iced intern");
+        let b = intern(&String::from("plotters-iced intern"));
+        assert!(std::ptr::eq(a, b));
+    }
+
+    #[test]
+    fn resolve_test() {
+        let font = Font::with_name("plotters-iced bundled");
+        assert_eq!(register("plotters-iced alias", font), None);
+        assert_eq!(resolve(FontFamily::Name("plotters-iced alias")), font);
+        assert_eq!(unregister("plotters-iced alias"), Some(font));
+        assert_eq!(
+            resolve(FontFamily::Name("plotters-iced alias")),
+            Font::with_name("plotters-iced alias")
+        );
+        assert_eq!(resolve(FontFamily::Monospace), Font::MONOSPACE);
+    }
+
+    #[test]
+    fn resolve_style_test() {
+        let font = resolve_style(FontFamily::Serif, FontStyle::Italic);
+        assert_eq!(font.family, font::Family::Serif);
+        assert_eq!(font.style, font::Style::Italic);
+        let font = resolve_style(FontFamily::Serif, FontStyle::Oblique);
+        assert_eq!(font.style, font::Style::Oblique);
+        let font = resolve_style(FontFamily::Serif, FontStyle::Bold);
+        assert_eq!(font.weight, font::Weight::Bold);
+        assert_eq!(font.style, font::Style::Normal);
+    }
+}
@@ -0,0 +1,176 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Multi-touch gestures
+//!
+//! Touch events are delivered to [`crate::Chart::update`] as they are. In addition, two fingers
+//! pressed within the chart are recognized as pinch and pan gestures, which are delivered to
+//! [`crate::Chart::on_gesture`].
+
+use iced_widget::core::{
+    touch::{self, Finger},
+    Point, Rectangle, Vector,
+};
+
+/// gesture recognized from touch events
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// two fingers moved towards or away from each other
+    Pinch {
+        /// center between the fingers
+        center: Point,
+        /// ratio of the new distance between the fingers to the previous one
+        scale: f32,
+    },
+    /// two fingers moved in the same direction
+    Pan {
+        /// center between the fingers, after the movement
+        center: Point,
+        /// movement of the center between the fingers
+        delta: Vector,
+    },
+}
+
+/// tracks fingers pressed within a chart
+#[derive(Debug, Default)]
+pub(crate) struct GestureRecognizer {
+    fingers: Vec<(Finger, Point)>,
+}
+
+impl GestureRecognizer {
+    /// updates the tracked fingers, returns the gestures made by the event
+    pub fn update(&mut self, event: &touch::Event, bounds: Rectangle) -> Vec<Gesture> {
+        match *event {
+            touch::Event::FingerPressed { id, position } => {
+                if bounds.contains(position) {
+                    self.fingers.retain(|(f, _)| *f != id);
+                    self.fingers.push((id, position));
+                }
+            }
+            touch::Event::FingerMoved { id, position } => {
+                let prev = self.two_fingers();
+                if let Some(finger) = self.fingers.iter_mut().find(|(f, _)| *f == id) {
+                    finger.1 = position;
+                }
+                if let (Some(prev), Some(next)) = (prev, self.two_fingers()) {
+                    return recognize(prev, next);
+                }
+            }
+            touch::Event::FingerLifted { id, .. } | touch::Event::FingerLost { id, .. } => {
+                self.fingers.retain(|(f, _)| *f != id);
+            }
+        }
+        Vec::new()
+    }
+
+    /// positions of the fingers, if exactly two fingers are pressed
+    fn two_fingers(&self) -> Option<[Point; 2]> {
+        match self.fingers.as_slice() {
+            [(_, a), (_, b)] => Some([*a, *b]),
+            _ => None,
+        }
+    }
+}
+
+/// recognizes the gestures of two fingers moving from `prev` to `next`
+fn recognize(prev: [Point; 2], next: [Point; 2]) -> Vec<Gesture> {
+    let center = |[a, b]: [Point; 2]| Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+    let (prev_center, next_center) = (center(prev), center(next));
+    let prev_distance = prev[0].distance(prev[1]);
+    let next_distance = next[0].distance(next[1]);
+
+    let mut gestures = Vec::with_capacity(2);
+    if prev_distance > 0.0 && next_distance != prev_distance {
+        gestures.push(Gesture::Pinch {
+            center: next_center,
+            scale: next_distance / prev_distance,
+        });
+    }
+    let delta = next_center - prev_center;
+    if delta != Vector::ZERO {
+        gestures.push(Gesture::Pan {
+            center: next_center,
+            delta,
+        });
+    }
+    gestures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: Rectangle = Rectangle {
+        x: 0.0,
+        y: 0.0,
+        width: 100.0,
+        height: 100.0,
+    };
+
+    fn press(recognizer: &mut GestureRecognizer, id: u64, x: f32, y: f32) {
+        let event = touch::Event::FingerPressed {
+            id: Finger(id),
+            position: Point::new(x, y),
+        };
+        assert!(recognizer.update(&event, BOUNDS).is_empty());
+    }
+
+    fn move_to(recognizer: &mut GestureRecognizer, id: u64, x: f32, y: f32) -> Vec<Gesture> {
+        let event = touch::Event::FingerMoved {
+            id: Finger(id),
+            position: Point::new(x, y),
+        };
+        recognizer.update(&event, BOUNDS)
+    }
+
+    #[test]
+    fn pinch_test() {
+        let mut recognizer = GestureRecognizer::default();
+        press(&mut recognizer, 1, 40.0, 50.0);
+        assert!(move_to(&mut recognizer, 1, 30.0, 50.0).is_empty());
+        press(&mut recognizer, 2, 70.0, 50.0);
+        let gestures = move_to(&mut recognizer, 2, 110.0, 50.0);
+        assert_eq!(
+            gestures,
+            vec![
+                Gesture::Pinch {
+                    center: Point::new(70.0, 50.0),
+                    scale: 2.0,
+                },
+                Gesture::Pan {
+                    center: Point::new(70.0, 50.0),
+                    delta: Vector::new(20.0, 0.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn pan_test() {
+        let mut recognizer = GestureRecognizer::default();
+        press(&mut recognizer, 1, 10.0, 10.0);
+        press(&mut recognizer, 2, 20.0, 10.0);
+        // fingers outside of the chart are not tracked
+        press(&mut recognizer, 3, 200.0, 10.0);
+        assert!(move_to(&mut recognizer, 3, 210.0, 10.0).is_empty());
+
+        move_to(&mut recognizer, 1, 10.0, 20.0);
+        let gestures = move_to(&mut recognizer, 2, 20.0, 20.0);
+        assert!(gestures.contains(&Gesture::Pan {
+            center: Point::new(15.0, 20.0),
+            delta: Vector::new(0.0, 5.0),
+        }));
+
+        recognizer.update(
+            &touch::Event::FingerLifted {
+                id: Finger(1),
+                position: Point::ORIGIN,
+            },
+            BOUNDS,
+        );
+        assert!(move_to(&mut recognizer, 2, 30.0, 30.0).is_empty());
+    }
+}
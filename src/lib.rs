@@ -11,16 +11,30 @@ pub use plotters_backend::DrawingBackend;
 #[doc(inline)]
 pub use chart::Chart;
 #[doc(inline)]
+pub use chart::Layer;
+#[doc(inline)]
 pub use chart::Renderer;
 #[doc(inline)]
 pub use error::Error;
 pub use widget::ChartWidget;
 
 mod backend;
+pub mod brush;
 mod chart;
+pub mod coords;
+mod crosshair;
 mod error;
+#[cfg(any(feature = "svg", feature = "png"))]
+pub mod export;
+pub mod fonts;
+pub mod gesture;
+pub mod precise;
 mod renderer;
 /// data point sampling
 pub mod sample;
+pub mod stroke;
+pub mod style;
+mod tooltip;
 mod utils;
 mod widget;
+pub mod zoom;
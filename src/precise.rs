@@ -0,0 +1,178 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Sub-pixel precision drawing
+//!
+//! plotters maps data to integer pixels, so lines snap to whole logical pixels, which looks
+//! jagged on HiDPI displays. Paths drawn by [`PreciseDrawingArea::draw_precise_path`] are mapped
+//! with `f64` precision and drawn as is by the iced backend; other backends draw them rounded.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::precise::PreciseDrawingArea;
+//!
+//! let mut chart = builder.build_cartesian_2d(0.0..10.0, -1.0..1.0)?;
+//! chart
+//!     .plotting_area()
+//!     .draw_precise_path((0..1000).map(|i| i as f64 / 100.0).map(|x| (x, x.sin())), &RED)?;
+//! ```
+
+use std::cell::RefCell;
+
+use plotters::{
+    coord::{
+        ranged1d::Ranged,
+        types::{RangedCoordf32, RangedCoordf64, RangedCoordi32, RangedCoordu32},
+        CoordTranslate, Shift,
+    },
+    drawing::{DrawingArea, DrawingAreaErrorKind},
+    element::PathElement,
+    prelude::Cartesian2d,
+    style::ShapeStyle,
+};
+use plotters_backend::DrawingBackend;
+
+thread_local! {
+    static PATH: RefCell<Option<Vec<[f64; 2]>>> = const { RefCell::new(None) };
+}
+
+/// ranged coordinates which can be mapped linearly with sub-pixel precision
+pub trait PreciseRanged: Ranged {
+    /// maps the value into the pixel range `limit`, without rounding
+    fn map_precise(&self, value: &Self::ValueType, limit: (i32, i32)) -> f64;
+}
+
+macro_rules! impl_precise_ranged {
+    ($($ranged:ty),*) => {
+        $(
+            impl PreciseRanged for $ranged {
+                #[inline]
+                fn map_precise(&self, value: &Self::ValueType, limit: (i32, i32)) -> f64 {
+                    let range = self.range();
+                    let (start, end) = (range.start as f64, range.end as f64);
+                    if start == end {
+                        // the middle of the pixel range
+                        return limit.0 as f64 + (limit.1 - limit.0) as f64 / 2.0;
+                    }
+                    map_linear(*value as f64, (start, end), (limit.0 as f64, limit.1 as f64))
+                }
+            }
+        )*
+    };
+}
+
+impl_precise_ranged!(
+    RangedCoordf32,
+    RangedCoordf64,
+    RangedCoordi32,
+    RangedCoordu32
+);
+
+/// coordinates which can be translated into backend coordinates with sub-pixel precision
+pub trait PreciseCoord: CoordTranslate {
+    /// translates the coordinate into backend coordinates, without rounding
+    fn translate_precise(&self, from: &Self::From) -> [f64; 2];
+}
+
+impl<X: PreciseRanged, Y: PreciseRanged> PreciseCoord for Cartesian2d<X, Y> {
+    fn translate_precise(&self, (x, y): &Self::From) -> [f64; 2] {
+        let (x_pixels, y_pixels) = pixel_ends(self);
+        [
+            self.x_spec().map_precise(x, x_pixels),
+            self.y_spec().map_precise(y, y_pixels),
+        ]
+    }
+}
+
+/// pixels of the ends of the x and the y ranges.
+///
+/// the ends of ranges are mapped exactly to the ends of pixel ranges, values in between are
+/// mapped linearly between them, see [`map_linear`].
+pub(crate) fn pixel_ends<X: Ranged, Y: Ranged>(
+    spec: &Cartesian2d<X, Y>,
+) -> ((i32, i32), (i32, i32)) {
+    let (x_range, y_range) = (spec.get_x_range(), spec.get_y_range());
+    let start = spec.translate(&(x_range.start, y_range.start));
+    let end = spec.translate(&(x_range.end, y_range.end));
+    ((start.0, end.0), (start.1, end.1))
+}
+
+/// maps the value linearly from the range `from` into the range `to`, `from` must not be empty
+#[inline]
+pub(crate) fn map_linear(value: f64, from: (f64, f64), to: (f64, f64)) -> f64 {
+    to.0 + (to.1 - to.0) * (value - from.0) / (from.1 - from.0)
+}
+
+/// drawing with sub-pixel precision on a [`DrawingArea`]
+pub trait PreciseDrawingArea<DB: DrawingBackend, CT: PreciseCoord> {
+    /// draws a path through the points with sub-pixel precision
+    fn draw_precise_path<I, S>(
+        &self,
+        points: I,
+        style: S,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>>
+    where
+        I: IntoIterator<Item = CT::From>,
+        S: Into<ShapeStyle>;
+}
+
+impl<DB: DrawingBackend, CT: PreciseCoord> PreciseDrawingArea<DB, CT> for DrawingArea<DB, CT> {
+    fn draw_precise_path<I, S>(
+        &self,
+        points: I,
+        style: S,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>>
+    where
+        I: IntoIterator<Item = CT::From>,
+        S: Into<ShapeStyle>,
+    {
+        let coord = self.as_coord_spec();
+        let (x0, y0) = self.get_base_pixel();
+        let (rounded, precise): (Vec<_>, Vec<_>) = points
+            .into_iter()
+            .map(|p| {
+                let (x, y) = coord.translate(&p);
+                ((x - x0, y - y0), coord.translate_precise(&p))
+            })
+            .unzip();
+        let area: DrawingArea<DB, Shift> = self.strip_coord_spec();
+        let element = PathElement::new(rounded, style);
+
+        // the iced backend takes the precise path when drawing the element
+        set_path(Some(precise));
+        let result = area.draw(&element);
+        set_path(None);
+        result
+    }
+}
+
+fn set_path(path: Option<Vec<[f64; 2]>>) {
+    PATH.with(|p| *p.borrow_mut() = path);
+}
+
+/// takes the precise path of the path being drawn
+pub(crate) fn take_path() -> Option<Vec<[f64; 2]>> {
+    PATH.with(|p| p.borrow_mut().take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_precise_test() {
+        let x: RangedCoordf64 = (0.0..10.0).into();
+        assert_eq!(x.map_precise(&2.5, (100, 200)), 125.0);
+        assert_eq!(x.map_precise(&0.25, (100, 200)), 102.5);
+        // y axis grows upwards
+        assert_eq!(x.map_precise(&0.25, (200, 100)), 197.5);
+        assert_eq!(x.map(&0.25, (100, 200)), 102);
+
+        // a single value is mapped to the middle of a plotting area away from the origin
+        let single: RangedCoordf64 = (5.0..5.0).into();
+        assert_eq!(single.map_precise(&5.0, (100, 200)), 150.0);
+    }
+}
@@ -4,32 +4,72 @@
 // Copyright: 2022, Joylei <leingliu@gmail.com>
 // License: MIT
 
+use std::cell::{Cell, RefCell};
+
 use iced_widget::{
-    canvas::{Cache, Frame},
-    core::{Layout, Size, Vector},
+    canvas::{Cache, Frame, Text},
+    core::{
+        alignment::{Horizontal, Vertical},
+        image::FilterMethod,
+        mouse::Cursor,
+        text, Color, Font, Layout, Rectangle, Size, Vector,
+    },
     renderer::Geometry,
     text::Shaping,
 };
-use plotters::prelude::DrawingArea;
+use plotters::{coord::Shift, drawing::DrawingAreaErrorKind, prelude::DrawingArea};
 
-use crate::backend::IcedChartBackend;
-use crate::Chart;
+use crate::backend::{Bitmap, IcedChartBackend};
+use crate::coords;
+use crate::crosshair;
+use crate::fonts::FontResolver;
+use crate::style::{self, Appearance};
+use crate::widget::{LayerState, WidgetState};
+use crate::zoom::{self, View};
+use crate::{Chart, Error};
 
 /// Graphics Renderer
 pub trait Renderer:
-    iced_widget::core::Renderer + iced_widget::core::text::Renderer + iced_graphics::geometry::Renderer
+    iced_widget::core::Renderer
+    + iced_widget::core::text::Renderer
+    + iced_widget::core::image::Renderer<Handle = iced_widget::core::image::Handle>
+    + iced_graphics::geometry::Renderer
 {
     /// draw a [Chart]
     fn draw_chart<Message, C>(
         &mut self,
-        state: &C::State,
+        state: &WidgetState<C::State>,
         chart: &C,
         layout: Layout<'_>,
-        shaping: Shaping,
+        cursor: Cursor,
+        options: &DrawOptions<'_>,
     ) where
         C: Chart<Message>;
 }
 
+thread_local! {
+    /// set by the default [`Chart::draw_overlay`], which draws nothing
+    static NO_OVERLAY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// marks the overlay of the chart drawn in progress as empty
+pub(crate) fn skip_overlay() {
+    NO_OVERLAY.with(|skipped| skipped.set(true));
+}
+
+/// options of drawing charts, set on [`crate::ChartWidget`]
+pub struct DrawOptions<'a> {
+    pub(crate) shaping: Shaping,
+    pub(crate) filter_method: FilterMethod,
+    /// merge consecutive shapes of the same style
+    pub(crate) batching: bool,
+    pub(crate) font_resolver: &'a FontResolver<'a>,
+    /// draw a crosshair at the cursor
+    pub(crate) crosshair: bool,
+    /// colors passed to the chart
+    pub(crate) appearance: &'a Appearance,
+}
+
 impl crate::chart::Renderer for iced_widget::renderer::Renderer {
     fn draw<F: Fn(&mut Frame)>(&self, size: Size, f: F) -> Geometry {
         let mut frame = Frame::new(self, size);
@@ -45,25 +85,391 @@ impl crate::chart::Renderer for iced_widget::renderer::Renderer {
 impl Renderer for iced_widget::renderer::Renderer {
     fn draw_chart<Message, C>(
         &mut self,
-        state: &C::State,
+        state: &WidgetState<C::State>,
         chart: &C,
         layout: Layout<'_>,
-        shaping: Shaping,
+        cursor: Cursor,
+        options: &DrawOptions<'_>,
     ) where
         C: Chart<Message>,
     {
+        let DrawOptions {
+            shaping,
+            filter_method,
+            crosshair,
+            ..
+        } = *options;
         let bounds = layout.bounds();
         if bounds.width < 1.0 || bounds.height < 1.0 {
             return;
         }
-        let geometry = chart.draw(self, bounds.size(), |frame| {
-            let backend = IcedChartBackend::new(frame, self, shaping);
-            let root: DrawingArea<_, _> = backend.into();
-            chart.draw_chart(state, root);
+        // caches of the chart are outdated if the view or the appearance changed since the last
+        // drawing
+        let view = state.zoom.view().cloned();
+        let appearance = options.appearance;
+        let invalidate = *state.drawn_view.borrow() != view
+            || state.drawn_appearance.borrow().as_ref() != Some(appearance);
+        if invalidate {
+            state.drawn_view.replace(view.clone());
+            state.drawn_appearance.replace(Some(appearance.clone()));
+        }
+        let renderer = ChartRenderer {
+            renderer: self,
+            invalidate,
+        };
+        let layers = chart.layers();
+        state
+            .layers
+            .borrow_mut()
+            .resize_with(layers.len().max(1), Default::default);
+        let layer_states = state.layers.borrow();
+        let new_frame = || Frame::new(self, bounds.size());
+        let redrawn = Cell::new(false);
+        let error = RefCell::new(None);
+        let report = |frame: &mut Frame, result: Result<(), Error>| {
+            redrawn.set(true);
+            if let Err(e) = result {
+                draw_error(frame, &e);
+                *error.borrow_mut() = Some(e);
+            }
+        };
+        // bitmaps and coordinates of the last drawing are kept if the chart is cached
+        let geometries: Vec<_> = if layers.is_empty() {
+            let geometry = chart.draw(&renderer, bounds.size(), |frame| {
+                let result = draw_layer::<Self, _>(
+                    frame,
+                    &new_frame,
+                    &layer_states[0],
+                    options,
+                    &view,
+                    |root| chart.try_draw_chart(&state.chart, root),
+                );
+                report(frame, result);
+            });
+            vec![geometry]
+        } else {
+            layers
+                .iter()
+                .zip(layer_states.iter())
+                .map(|(layer, layer_state)| {
+                    crate::chart::Renderer::draw_cache(
+                        &renderer,
+                        &layer.cache,
+                        bounds.size(),
+                        |frame| {
+                            let result = draw_layer::<Self, _>(
+                                frame,
+                                &new_frame,
+                                layer_state,
+                                options,
+                                &view,
+                                |root| chart.draw_layer(&state.chart, layer, root),
+                            );
+                            report(frame, result);
+                        },
+                    )
+                })
+                .collect()
+        };
+        if redrawn.get() {
+            state.collect_coords();
+            state.error.replace(error.into_inner());
+        }
+        // cursor dependent content is drawn on every frame, so the chart itself stays cached
+        let captured = state.coords();
+        let position = cursor.position_in(bounds).filter(|_| crosshair);
+        NO_OVERLAY.with(|skipped| skipped.set(false));
+        let overlay = crate::chart::Renderer::draw(&*self, bounds.size(), |frame| {
+            coords::with_captured(captured.clone(), || {
+                if let Some(position) = position {
+                    crosshair::draw::<<Self as text::Renderer>::Paragraph>(
+                        frame,
+                        &captured,
+                        position,
+                        shaping,
+                        options.appearance,
+                    );
+                }
+                state.brush.draw(frame, options.appearance);
+                chart.draw_overlay(&state.chart, frame, bounds, cursor);
+            });
         });
+        let empty = position.is_none() && !state.brush.is_dragging() && NO_OVERLAY.with(Cell::get);
+        // layers are composed in order, each with its bitmaps in the drawing order of plotters
+        let mut items = Vec::new();
+        for (geometry, layer) in geometries.into_iter().zip(layer_states.iter()) {
+            for segment in layer.segments.borrow_mut().iter_mut() {
+                items.push(Item::Geometry(vec![segment.geometry(self, bounds.size())]));
+                items.push(Item::Bitmaps(segment.bitmaps.clone()));
+            }
+            items.push(Item::Geometry(vec![geometry]));
+        }
+        draw_items(self, bounds, filter_method, items);
+        if empty {
+            return;
+        }
+        // a new layer puts the overlay above the bitmaps
         let translation = Vector::new(bounds.x, bounds.y);
-        iced_widget::core::Renderer::with_translation(self, translation, |renderer| {
-            iced_graphics::geometry::Renderer::draw(renderer, vec![geometry]);
+        iced_widget::core::Renderer::with_layer(self, bounds, |renderer| {
+            iced_widget::core::Renderer::with_translation(renderer, translation, |renderer| {
+                iced_graphics::geometry::Renderer::draw(renderer, vec![overlay]);
+            });
         });
     }
 }
+
+/// draws a layer of the chart with plotters for the text renderer `B`, keeps its bitmaps and
+/// coordinates
+fn draw_layer<B, F>(
+    frame: &mut Frame,
+    new_frame: &dyn Fn() -> Frame,
+    layer: &LayerState,
+    options: &DrawOptions<'_>,
+    view: &Option<View>,
+    f: F,
+) -> Result<(), Error>
+where
+    B: text::Renderer<Font = Font>,
+    F: FnOnce(
+        DrawingArea<IcedChartBackend<'_, B>, Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<Error>>,
+{
+    layer.segments.borrow_mut().clear();
+    let backend = IcedChartBackend::<B>::new(
+        frame,
+        new_frame,
+        options.shaping,
+        options.batching,
+        &layer.segments,
+        options.font_resolver,
+    );
+    let (result, coords) = style::with_appearance(options.appearance.clone(), || {
+        zoom::with_view(view.clone(), || coords::capture(|| f(backend.into())))
+    });
+    *layer.coords.borrow_mut() = coords;
+    result.map_err(Error::from)
+}
+
+/// geometry or bitmaps of a chart
+enum Item {
+    Geometry(Vec<Geometry>),
+    Bitmaps(Vec<Bitmap>),
+}
+
+/// draws the items in order.
+///
+/// within a layer of iced, images are drawn above meshes and texts above images, so every run of
+/// geometry or bitmaps but the first is drawn in a new layer.
+fn draw_items(
+    renderer: &mut iced_widget::renderer::Renderer,
+    bounds: Rectangle,
+    filter_method: FilterMethod,
+    items: Vec<Item>,
+) {
+    let mut runs: Vec<Item> = Vec::with_capacity(items.len());
+    for item in items {
+        match (runs.last_mut(), item) {
+            (Some(Item::Geometry(run)), Item::Geometry(geometries)) => run.extend(geometries),
+            (Some(Item::Bitmaps(run)), Item::Bitmaps(bitmaps)) => run.extend(bitmaps),
+            (_, item) => runs.push(item),
+        }
+    }
+    let translation = Vector::new(bounds.x, bounds.y);
+    for (i, run) in runs.into_iter().enumerate() {
+        let draw = |renderer: &mut iced_widget::renderer::Renderer| {
+            iced_widget::core::Renderer::with_translation(renderer, translation, |renderer| {
+                match run {
+                    Item::Geometry(geometries) => {
+                        iced_graphics::geometry::Renderer::draw(renderer, geometries)
+                    }
+                    Item::Bitmaps(bitmaps) => {
+                        for bitmap in bitmaps {
+                            iced_widget::core::image::Renderer::draw(
+                                renderer,
+                                bitmap.handle,
+                                filter_method,
+                                bitmap.bounds,
+                            );
+                        }
+                    }
+                }
+            });
+        };
+        if i == 0 {
+            draw(renderer);
+        } else {
+            iced_widget::core::Renderer::with_layer(renderer, bounds, draw);
+        }
+    }
+}
+
+/// renderer handed to [`Chart::draw`], clears caches of charts whose view changed
+struct ChartRenderer<'a> {
+    renderer: &'a iced_widget::renderer::Renderer,
+    invalidate: bool,
+}
+
+impl crate::chart::Renderer for ChartRenderer<'_> {
+    fn draw<F: Fn(&mut Frame)>(&self, size: Size, f: F) -> Geometry {
+        self.renderer.draw(size, f)
+    }
+
+    fn draw_cache<F: Fn(&mut Frame)>(&self, cache: &Cache, size: Size, f: F) -> Geometry {
+        if self.invalidate {
+            cache.clear();
+        }
+        self.renderer.draw_cache(cache, size, f)
+    }
+}
+
+/// draws the error in place of the chart
+fn draw_error(frame: &mut Frame, error: &Error) {
+    frame.fill_text(Text {
+        content: error.to_string(),
+        position: frame.center(),
+        color: Color::from_rgb8(0xd0, 0x30, 0x30),
+        horizontal_alignment: Horizontal::Center,
+        vertical_alignment: Vertical::Center,
+        ..Default::default()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use iced_widget::core::{
+        layout::{self, Layout},
+        renderer::Style,
+        widget::Tree,
+        Pixels, Point, Rectangle, Renderer as _, Widget,
+    };
+    use plotters_backend::DrawingBackend;
+
+    use super::*;
+    use crate::coords::CaptureCoords;
+    use crate::{ChartBuilder, ChartWidget, Layer};
+
+    struct LayeredChart {
+        layers: [Layer; 2],
+        draws: [Cell<usize>; 2],
+    }
+
+    impl Chart<()> for LayeredChart {
+        type State = ();
+
+        fn build_chart<DB: DrawingBackend>(
+            &self,
+            _state: &Self::State,
+            _builder: ChartBuilder<DB>,
+        ) {
+        }
+
+        fn layers(&self) -> &[Layer] {
+            &self.layers
+        }
+
+        fn draw_layer<DB: DrawingBackend>(
+            &self,
+            _state: &Self::State,
+            layer: &Layer,
+            root: DrawingArea<DB, Shift>,
+        ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+            let index = self.layers.iter().position(|l| l.name() == layer.name());
+            let draws = &self.draws[index.unwrap()];
+            draws.set(draws.get() + 1);
+            // both layers draw the same chart
+            let chart = ChartBuilder::on(&root).build_cartesian_2d(0.0..1.0, 0.0..1.0)?;
+            chart.capture_coords();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn layers_test() {
+        let mut renderer =
+            iced_widget::renderer::Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+                iced_tiny_skia::Backend::new(),
+                Font::DEFAULT,
+                Pixels(16.0),
+            ));
+        let chart = LayeredChart {
+            layers: [Layer::new("mesh"), Layer::new("series")],
+            draws: Default::default(),
+        };
+        let widget = ChartWidget::new(&chart);
+        let tree = Tree::new(&widget as &dyn Widget<(), iced_widget::Theme, _>);
+        let node = layout::Node::new(Size::new(100.0, 100.0));
+        let mut draw = || {
+            renderer.clear();
+            widget.draw(
+                &tree,
+                &mut renderer,
+                &iced_widget::Theme::Light,
+                &Style::default(),
+                Layout::new(&node),
+                Cursor::Unavailable,
+                &Rectangle::with_size(Size::new(100.0, 100.0)),
+            );
+        };
+
+        draw();
+        draw();
+        assert_eq!(chart.draws[0].get(), 1);
+        assert_eq!(chart.draws[1].get(), 1);
+
+        chart.layers[1].clear();
+        draw();
+        assert_eq!(chart.draws[0].get(), 1);
+        assert_eq!(chart.draws[1].get(), 2);
+
+        // coordinates of the same chart drawn by both layers are kept once
+        let state = tree.state.downcast_ref::<WidgetState<()>>();
+        assert_eq!(state.layers.borrow()[0].coords.borrow().len(), 1);
+        assert_eq!(state.coords().len(), 1);
+    }
+
+    #[test]
+    fn cursor_test() {
+        let mut renderer =
+            iced_widget::renderer::Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+                iced_tiny_skia::Backend::new(),
+                Font::DEFAULT,
+                Pixels(16.0),
+            ));
+        let chart = LayeredChart {
+            layers: [Layer::new("mesh"), Layer::new("series")],
+            draws: Default::default(),
+        };
+        let widget = ChartWidget::new(&chart).crosshair();
+        let tree = Tree::new(&widget as &dyn Widget<(), iced_widget::Theme, _>);
+        let node = layout::Node::new(Size::new(100.0, 100.0));
+        // returns the number of primitives drawn
+        let mut draw = |cursor| {
+            renderer.clear();
+            widget.draw(
+                &tree,
+                &mut renderer,
+                &iced_widget::Theme::Light,
+                &Style::default(),
+                Layout::new(&node),
+                cursor,
+                &Rectangle::with_size(Size::new(100.0, 100.0)),
+            );
+            let iced_widget::renderer::Renderer::TinySkia(renderer) = &mut renderer else {
+                unreachable!()
+            };
+            renderer.with_primitives(|_, primitives| primitives.len())
+        };
+
+        let hidden = draw(Cursor::Unavailable);
+        for x in [10.0, 20.0, 30.0] {
+            let shown = draw(Cursor::Available(Point::new(x, 50.0)));
+            // only the crosshair is drawn above the cached chart
+            assert_eq!(shown, hidden + 1);
+        }
+        assert_eq!(draw(Cursor::Unavailable), hidden);
+        assert_eq!(chart.draws[0].get(), 1);
+        assert_eq!(chart.draws[1].get(), 1);
+    }
+}
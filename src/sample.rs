@@ -5,4 +5,9 @@
 // License: MIT
 //
 
+pub mod gaps;
+pub mod indices;
 pub mod lttb;
+pub mod minmax;
+pub mod minmax_lttb;
+pub mod stream;
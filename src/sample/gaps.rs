@@ -0,0 +1,291 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+//
+
+//! Gap-aware sampling
+//!
+//! points with non-finite x or y values, e.g. `NaN`, break a series into segments. Each segment
+//! is sampled on its own, with a share of the threshold, and [`Sample::Gap`] markers separate
+//! the segments, so gaps are neither bridged by lines nor poison the buckets of the samplers.
+//! Missing values can be mapped to `NaN` with [`LttbSource::cast`].
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::sample::{gaps, lttb::LttbSource};
+//!
+//! // `Point` implements `DataPoint`, readings have optional values
+//! let samples = readings
+//!     .as_slice()
+//!     .cast(|reading| Point::new(reading.time, reading.value.unwrap_or(f64::NAN)))
+//!     .gaps()
+//!     .lttb(1000);
+//! for segment in gaps::segments(samples) {
+//!     chart.draw_series(LineSeries::new(segment.into_iter().map(|p| (p.x, p.y)), &RED))?;
+//! }
+//! ```
+
+use std::ops::Range;
+
+use super::indices::Indexed;
+use super::lttb::{DataPoint, LttbSource};
+
+/// sampled point, or a gap between segments
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sample<T> {
+    /// sampled point
+    Point(T),
+    /// break between two segments
+    Gap,
+}
+
+impl<T> Sample<T> {
+    /// the sampled point, `None` for gaps
+    pub fn point(self) -> Option<T> {
+        match self {
+            Sample::Point(point) => Some(point),
+            Sample::Gap => None,
+        }
+    }
+}
+
+/// splits samples at gaps, e.g. to draw a `LineSeries` per segment
+pub fn segments<T>(samples: impl IntoIterator<Item = Sample<T>>) -> Vec<Vec<T>> {
+    let mut segments = vec![Vec::new()];
+    for sample in samples {
+        match sample {
+            Sample::Point(point) => segments.last_mut().unwrap().push(point),
+            Sample::Gap => segments.push(Vec::new()),
+        }
+    }
+    segments.retain(|segment| !segment.is_empty());
+    segments
+}
+
+/// gap-aware samplers, see [`LttbSource::gaps`]
+pub struct Gaps<S: LttbSource> {
+    source: S,
+}
+
+impl<S: LttbSource> Gaps<S>
+where
+    S::Item: DataPoint,
+{
+    pub(crate) fn new(source: S) -> Self {
+        Self { source }
+    }
+
+    /// lttb sampling of each segment, with about `threshold` points in total
+    ///
+    /// segments keep at least 3 points, or all of their points if they have fewer.
+    pub fn lttb(self, threshold: usize) -> GapIterator<S> {
+        let samples = sample(&self.source, threshold, count, |segment, share| {
+            segment
+                .lttb(share.max(3))
+                .map(|point| point.index())
+                .collect()
+        });
+        GapIterator::new(self.source, samples)
+    }
+
+    /// min-max sampling of each segment, with about `threshold` points in total
+    pub fn min_max(self, threshold: usize) -> GapIterator<S> {
+        let samples = sample(&self.source, threshold, count, |segment, share| {
+            segment
+                .min_max(share.max(2))
+                .map(|point| point.index())
+                .collect()
+        });
+        GapIterator::new(self.source, samples)
+    }
+
+    /// M4 sampling of each segment, with `columns` shared by the x ranges of the segments
+    pub fn m4(self, columns: usize) -> GapIterator<S> {
+        let samples = sample(&self.source, columns, span, |segment, share| {
+            segment
+                .m4(share.max(1))
+                .map(|point| point.index())
+                .collect()
+        });
+        GapIterator::new(self.source, samples)
+    }
+}
+
+/// iterator for [`Gaps`]
+pub struct GapIterator<S: LttbSource> {
+    source: S,
+    /// indices of the sampled items, `None` for gaps
+    samples: Vec<Option<usize>>,
+    idx: usize,
+}
+
+impl<S: LttbSource> GapIterator<S> {
+    fn new(source: S, samples: Vec<Option<usize>>) -> Self {
+        Self {
+            source,
+            samples,
+            idx: 0,
+        }
+    }
+}
+
+impl<S: LttbSource> Iterator for GapIterator<S> {
+    type Item = Sample<S::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = *self.samples.get(self.idx)?;
+        self.idx += 1;
+        Some(match sample {
+            Some(i) => Sample::Point(self.source.item_at(i)),
+            None => Sample::Gap,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.samples.len() - self.idx;
+        (size, Some(size))
+    }
+}
+
+impl<S: LttbSource> ExactSizeIterator for GapIterator<S> {}
+
+/// finite points of a source
+type Segment<'a, S> = Indexed<&'a S>;
+
+/// ranges of consecutive finite points
+fn split<S>(source: &S) -> Vec<Range<usize>>
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+{
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for i in 0..source.len() {
+        let item = source.item_at(i);
+        let finite = item.x().is_finite() && item.y().is_finite();
+        match (finite, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                ranges.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        ranges.push(s..source.len());
+    }
+    ranges
+}
+
+/// weight of a segment by its number of points
+fn count<S>(segment: &Segment<'_, S>) -> f64
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+{
+    segment.len() as f64
+}
+
+/// weight of a segment by its x range
+fn span<S>(segment: &Segment<'_, S>) -> f64
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+{
+    segment.item_at(segment.len() - 1).x() - segment.item_at(0).x()
+}
+
+/// samples each segment with its share of `budget`, separated by gaps
+fn sample<S, W, F>(source: &S, budget: usize, weight: W, f: F) -> Vec<Option<usize>>
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+    W: Fn(&Segment<'_, S>) -> f64,
+    F: Fn(Segment<'_, S>, usize) -> Vec<usize>,
+{
+    let segments: Vec<_> = split(source)
+        .into_iter()
+        .map(|range| Indexed::range(source, range))
+        .collect();
+    let total: f64 = segments.iter().map(&weight).sum();
+    let mut samples = Vec::new();
+    for (n, segment) in segments.into_iter().enumerate() {
+        if n > 0 {
+            samples.push(None);
+        }
+        let share = if total > 0.0 {
+            (budget as f64 * weight(&segment) / total).round() as usize
+        } else {
+            0
+        };
+        samples.extend(f(segment, share).into_iter().map(Some));
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::lttb::tests::Point;
+
+    /// two segments of a sine wave with missing values between them
+    fn data() -> Vec<Point> {
+        (0..1000)
+            .map(|i| {
+                let x = i as f64;
+                let y = if (400..500).contains(&i) {
+                    f64::NAN
+                } else {
+                    (x / 50.0).sin()
+                };
+                Point(x, y)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gaps_test() {
+        let data = data();
+        for samples in [
+            data.as_slice().gaps().lttb(100),
+            data.as_slice().gaps().min_max(100),
+            data.as_slice().gaps().m4(25),
+        ] {
+            let samples: Vec<_> = samples.collect();
+            assert_eq!(
+                samples
+                    .iter()
+                    .filter(|sample| **sample == Sample::Gap)
+                    .count(),
+                1
+            );
+            let segments = segments(samples);
+            assert_eq!(segments.len(), 2);
+            // no point is sampled from the gap, and segments end at its borders
+            assert!(segments[0].iter().all(|point| point.1.is_finite()));
+            assert!(segments[0].last().unwrap().0 < 400.0);
+            assert!(segments[1].first().unwrap().0 >= 500.0);
+        }
+
+        let lttb: Vec<_> = data.as_slice().gaps().lttb(100).collect();
+        assert!(lttb.len() <= 101);
+        assert_eq!(lttb[0], Sample::Point(&data[0]));
+        assert_eq!(lttb.last(), Some(&Sample::Point(&data[999])));
+    }
+
+    #[test]
+    fn segments_test() {
+        let samples = [
+            Sample::Gap,
+            Sample::Point(1),
+            Sample::Point(2),
+            Sample::Gap,
+            Sample::Point(3),
+        ];
+        assert_eq!(segments(samples), vec![vec![1, 2], vec![3]]);
+    }
+}
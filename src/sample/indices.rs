@@ -0,0 +1,163 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+//
+
+//! Index-returning sampling
+//!
+//! the `*_indices` methods of [`LttbSource`] return the indices of the sampled items instead of
+//! the items, so they can be used against parallel arrays or columnar data, e.g. to look up the
+//! metadata of sampled points, without cloning items.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::sample::lttb::LttbSource;
+//!
+//! let points = times.iter().zip(values.iter()).map(|(x, y)| Point::new(*x, *y)).collect::<Vec<_>>();
+//! for i in points.as_slice().lttb_indices(1000) {
+//!     println!("{} at {}", event_ids[i], times[i]);
+//! }
+//! ```
+
+use std::ops::Range;
+
+use super::lttb::{DataPoint, LttbSource};
+
+/// data point with its index in the source, see [`Indexed`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndexedPoint {
+    index: usize,
+    x: f64,
+    y: f64,
+}
+
+impl IndexedPoint {
+    /// index of the item in the source
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl DataPoint for IndexedPoint {
+    #[inline]
+    fn x(&self) -> f64 {
+        self.x
+    }
+
+    #[inline]
+    fn y(&self) -> f64 {
+        self.y
+    }
+}
+
+/// items of a source within a range, as [`IndexedPoint`]s
+pub struct Indexed<S: LttbSource> {
+    source: S,
+    range: Range<usize>,
+}
+
+impl<S: LttbSource> Indexed<S> {
+    /// all items of the source
+    pub(crate) fn new(source: S) -> Self {
+        let range = 0..source.len();
+        Self { source, range }
+    }
+
+    /// items of the source within `range`
+    pub(crate) fn range(source: S, range: Range<usize>) -> Self {
+        Self { source, range }
+    }
+}
+
+impl<S> LttbSource for Indexed<S>
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+{
+    type Item = IndexedPoint;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    #[inline]
+    fn item_at(&self, i: usize) -> Self::Item {
+        let index = self.range.start + i;
+        let item = self.source.item_at(index);
+        IndexedPoint {
+            index,
+            x: item.x(),
+            y: item.y(),
+        }
+    }
+}
+
+/// iterator over the indices of sampled items
+pub struct Indices<I> {
+    inner: I,
+}
+
+impl<I> Indices<I> {
+    pub(crate) fn new(inner: I) -> Self {
+        Self { inner }
+    }
+}
+
+impl<I: Iterator<Item = IndexedPoint>> Iterator for Indices<I> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|point| point.index)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator<Item = IndexedPoint>> ExactSizeIterator for Indices<I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::lttb::tests::{noise, Point};
+
+    #[test]
+    fn indices_test() {
+        let data: Vec<_> = (0..1000).map(|i| Point(i as f64, noise(i))).collect();
+
+        let lttb: Vec<_> = data.as_slice().lttb(50).copied().collect();
+        let indices = data.as_slice().lttb_indices(50);
+        assert_eq!(indices.len(), 50);
+        let lookup: Vec<_> = indices.map(|i| data[i]).collect();
+        assert_eq!(lttb, lookup);
+
+        let min_max: Vec<_> = data.as_slice().min_max(50).copied().collect();
+        let lookup: Vec<_> = data
+            .as_slice()
+            .min_max_indices(50)
+            .map(|i| data[i])
+            .collect();
+        assert_eq!(min_max, lookup);
+
+        let m4: Vec<_> = data.as_slice().m4(10).copied().collect();
+        let lookup: Vec<_> = data.as_slice().m4_indices(10).map(|i| data[i]).collect();
+        assert_eq!(m4, lookup);
+
+        let minmax_lttb: Vec<_> = data.as_slice().minmax_lttb(50, 4).copied().collect();
+        let lookup: Vec<_> = data
+            .as_slice()
+            .minmax_lttb_indices(50, 4)
+            .map(|i| data[i])
+            .collect();
+        assert_eq!(minmax_lttb, lookup);
+
+        // not sampled
+        assert!(data.as_slice().lttb_indices(1000).eq(0..1000));
+    }
+}
@@ -13,6 +13,11 @@
 // original version: https://github.com/sveinn-steinarsson/flot-downsample
 // modified based on https://github.com/jeromefroe/lttb-rs
 
+use super::gaps::Gaps;
+use super::indices::{Indexed, Indices};
+use super::minmax::{self, MinMaxIterator};
+use super::minmax_lttb::{self, Selected};
+
 /// data point for [`LttbSource`]
 pub trait DataPoint {
     /// x value for sampling, must be in a strictly increasing order
@@ -84,6 +89,99 @@ pub trait LttbSource {
             every,
         }
     }
+
+    /// min-max sampling, keeps the minimum and the maximum of `threshold / 2` buckets of equal
+    /// length, see [`crate::sample::minmax`]
+    fn min_max(self, threshold: usize) -> MinMaxIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: DataPoint,
+    {
+        let indices = minmax::min_max_indices(&self, threshold);
+        MinMaxIterator::new(self, indices)
+    }
+
+    /// M4 sampling, keeps the first, minimum, maximum and last points of `columns` equal x
+    /// ranges, e.g. pixel columns, see [`crate::sample::minmax`]
+    fn m4(self, columns: usize) -> MinMaxIterator<Self>
+    where
+        Self: Sized,
+        Self::Item: DataPoint,
+    {
+        let indices = minmax::m4_indices(&self, columns);
+        MinMaxIterator::new(self, indices)
+    }
+
+    /// MinMaxLTTB sampling, runs LTTB on the minimum and the maximum of
+    /// `threshold * ratio / 2` buckets, see [`crate::sample::minmax_lttb`]
+    fn minmax_lttb(self, threshold: usize, ratio: usize) -> LttbIterator<Selected<Self>>
+    where
+        Self: Sized,
+        Self::Item: DataPoint,
+    {
+        let indices = minmax_lttb::preselect(&self, threshold, ratio);
+        Selected::new(self, indices).lttb(threshold)
+    }
+
+    /// indices of the items selected by [`LttbSource::lttb`], see [`crate::sample::indices`]
+    fn lttb_indices(self, threshold: usize) -> Indices<LttbIterator<Indexed<Self>>>
+    where
+        Self: Sized,
+        Self::Item: DataPoint,
+    {
+        Indices::new(Indexed::new(self).lttb(threshold))
+    }
+
+    /// indices of the items selected by [`LttbSource::min_max`]
+    fn min_max_indices(self, threshold: usize) -> Indices<MinMaxIterator<Indexed<Self>>>
+    where
+        Self: Sized,
+        Self::Item: DataPoint,
+    {
+        Indices::new(Indexed::new(self).min_max(threshold))
+    }
+
+    /// indices of the items selected by [`LttbSource::m4`]
+    fn m4_indices(self, columns: usize) -> Indices<MinMaxIterator<Indexed<Self>>>
+    where
+        Self: Sized,
+        Self::Item: DataPoint,
+    {
+        Indices::new(Indexed::new(self).m4(columns))
+    }
+
+    /// indices of the items selected by [`LttbSource::minmax_lttb`]
+    fn minmax_lttb_indices(
+        self,
+        threshold: usize,
+        ratio: usize,
+    ) -> Indices<LttbIterator<Selected<Indexed<Self>>>>
+    where
+        Self: Sized,
+        Self::Item: DataPoint,
+    {
+        Indices::new(Indexed::new(self).minmax_lttb(threshold, ratio))
+    }
+
+    /// gap-aware sampling, breaks the source into segments at non-finite values, see
+    /// [`crate::sample::gaps`]
+    fn gaps(self) -> Gaps<Self>
+    where
+        Self: Sized,
+        Self::Item: DataPoint,
+    {
+        Gaps::new(self)
+    }
+
+    /// [`LttbSource::minmax_lttb`] with the buckets scanned in parallel chunks
+    fn par_minmax_lttb(self, threshold: usize, ratio: usize) -> LttbIterator<Selected<Self>>
+    where
+        Self: Sized + Sync,
+        Self::Item: DataPoint,
+    {
+        let indices = minmax_lttb::par_preselect(&self, threshold, ratio);
+        Selected::new(self, indices).lttb(threshold)
+    }
 }
 
 /// map data item to another type
@@ -116,7 +214,7 @@ where
     }
 }
 
-impl<'a, S: LttbSource> LttbSource for &'a S {
+impl<S: LttbSource> LttbSource for &S {
     type Item = S::Item;
     #[inline]
     fn len(&self) -> usize {
@@ -165,53 +263,20 @@ where
                 let every = self.every;
                 let i = self.idx - 1;
                 // Calculate point average for next bucket (containing c).
-                let mut avg_x = 0f64;
-                let mut avg_y = 0f64;
-
                 let avg_range_start = (((i + 1) as f64) * every) as usize + 1;
-
-                let mut end = (((i + 2) as f64) * every) as usize + 1;
-                if end >= self.source.len() {
-                    end = self.source.len();
-                }
-                let avg_range_end = end;
-
-                let avg_range_length = (avg_range_end - avg_range_start) as f64;
-
-                for i in 0..(avg_range_end - avg_range_start) {
-                    let idx = avg_range_start + i;
-                    let item = self.source.item_at(idx);
-                    avg_x += item.x();
-                    avg_y += item.y();
-                }
-                avg_x /= avg_range_length;
-                avg_y /= avg_range_length;
+                let avg_range_end =
+                    ((((i + 2) as f64) * every) as usize + 1).min(self.source.len());
+                let average =
+                    average((avg_range_start..avg_range_end).map(|idx| self.source.item_at(idx)));
 
                 // Get the range for this bucket.
                 let range_offs = ((i as f64) * every) as usize + 1;
                 let range_to = (((i + 1) as f64) * every) as usize + 1;
 
                 // Point a.
-                let item = self.source.item_at(self.a);
-                let point_a_x = item.x();
-                let point_a_y = item.y();
-
-                let mut max_area = -1f64;
-                let mut next_a = range_offs;
-                for i in 0..(range_to - range_offs) {
-                    let idx = range_offs + i;
-
-                    // Calculate triangle area over three buckets.
-                    let item = self.source.item_at(idx);
-                    let area = ((point_a_x - avg_x) * (item.y() - point_a_y)
-                        - (point_a_x - item.x()) * (avg_y - point_a_y))
-                        .abs()
-                        * 0.5;
-                    if area > max_area {
-                        max_area = area;
-                        next_a = idx; // Next a is this b.
-                    }
-                }
+                let a = self.source.item_at(self.a);
+                let bucket = (range_offs..range_to).map(|idx| self.source.item_at(idx));
+                let next_a = range_offs + select(&a, bucket, average); // Next a is this b.
 
                 let item = self.source.item_at(next_a); // Pick this point from the bucket.
                 self.a = next_a; // This a is the next a (chosen b).
@@ -289,8 +354,38 @@ impl<T: Clone> LttbSource for [T] {
     }
 }
 
+/// average point of a bucket
+pub(super) fn average<T: DataPoint>(bucket: impl ExactSizeIterator<Item = T>) -> (f64, f64) {
+    let len = bucket.len() as f64;
+    let (x, y) = bucket.fold((0.0, 0.0), |(x, y), item| (x + item.x(), y + item.y()));
+    (x / len, y / len)
+}
+
+/// index of the point of the bucket forming the largest triangle with `a` and the average of the
+/// next bucket
+pub(super) fn select<T: DataPoint>(
+    a: &impl DataPoint,
+    bucket: impl Iterator<Item = T>,
+    (avg_x, avg_y): (f64, f64),
+) -> usize {
+    let (a_x, a_y) = (a.x(), a.y());
+    let mut max_area = -1f64;
+    let mut selected = 0;
+    for (i, item) in bucket.enumerate() {
+        // Calculate triangle area over three buckets.
+        let area =
+            ((a_x - avg_x) * (item.y() - a_y) - (a_x - item.x()) * (avg_y - a_y)).abs() * 0.5;
+        if area > max_area {
+            max_area = area;
+            selected = i;
+        }
+    }
+    selected
+}
+
 #[cfg(test)]
-mod tests {
+#[allow(clippy::vec_init_then_push)]
+pub(super) mod tests {
     use super::*;
 
     #[derive(Debug, PartialEq, Clone)]
@@ -315,6 +410,25 @@ mod tests {
         }
     }
 
+    /// data point shared by the tests of the samplers
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub(in crate::sample) struct Point(pub f64, pub f64);
+
+    impl super::DataPoint for Point {
+        fn x(&self) -> f64 {
+            self.0
+        }
+
+        fn y(&self) -> f64 {
+            self.1
+        }
+    }
+
+    /// deterministic noise between 0 and 1
+    pub(in crate::sample) fn noise(i: usize) -> f64 {
+        ((i * 7919) % 101) as f64 / 100.0
+    }
+
     #[test]
     fn lttb_test() {
         let mut dps = vec![];
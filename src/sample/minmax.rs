@@ -0,0 +1,235 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+//
+
+//! Min-max and M4 downsampling
+//!
+//! unlike LTTB, which picks one point per bucket, these samplers keep the extremes of each
+//! bucket, so single-sample spikes are never dropped.
+//! - min-max keeps the minimum and the maximum of buckets of equal length
+//! - M4 keeps the first, minimum, maximum and last points of buckets of equal x ranges, e.g.
+//!   pixel columns
+//!
+//! ## Known limitations
+//! - X-values must be in a strictly increasing order
+
+use super::lttb::{DataPoint, LttbSource};
+
+/// iterator for [`LttbSource::min_max`] and [`LttbSource::m4`]
+pub struct MinMaxIterator<S: LttbSource> {
+    source: S,
+    /// indices of the selected items, `None` if all items are selected
+    indices: Option<Vec<usize>>,
+    idx: usize,
+}
+
+impl<S: LttbSource> MinMaxIterator<S> {
+    pub(crate) fn new(source: S, indices: Option<Vec<usize>>) -> Self {
+        Self {
+            source,
+            indices,
+            idx: 0,
+        }
+    }
+
+    #[inline]
+    fn remaining(&self) -> usize {
+        match &self.indices {
+            Some(indices) => indices.len() - self.idx,
+            None => self.source.len() - self.idx,
+        }
+    }
+}
+
+impl<S: LttbSource> Iterator for MinMaxIterator<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining() == 0 {
+            return None;
+        }
+        let i = match &self.indices {
+            Some(indices) => indices[self.idx],
+            None => self.idx,
+        };
+        self.idx += 1;
+        Some(self.source.item_at(i))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.remaining();
+        (size, Some(size))
+    }
+}
+
+impl<S: LttbSource> ExactSizeIterator for MinMaxIterator<S> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.remaining()
+    }
+}
+
+/// indices of the minimum and the maximum of `threshold / 2` buckets, `None` if all items are
+/// selected
+pub(crate) fn min_max_indices<S>(source: &S, threshold: usize) -> Option<Vec<usize>>
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+{
+    let len = source.len();
+    let buckets = threshold / 2;
+    if threshold >= len || buckets == 0 {
+        return None;
+    }
+    let mut indices = Vec::with_capacity(buckets * 2);
+    for bucket in 0..buckets {
+        let start = bucket_start(bucket, len, buckets);
+        let end = bucket_start(bucket + 1, len, buckets);
+        let (min, max) = extremes(source, start, end);
+        push_sorted(&mut indices, &[min, max]);
+    }
+    Some(indices)
+}
+
+/// indices of the first, minimum, maximum and last items of `columns` equal x ranges, `None` if
+/// all items are selected
+pub(crate) fn m4_indices<S>(source: &S, columns: usize) -> Option<Vec<usize>>
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+{
+    let len = source.len();
+    if columns == 0 || len <= columns * 4 {
+        return None;
+    }
+    let first = source.item_at(0).x();
+    let width = (source.item_at(len - 1).x() - first) / columns as f64;
+    if !(width > 0.0 && width.is_finite()) {
+        return None;
+    }
+    let column_of = |i: usize| {
+        let column = ((source.item_at(i).x() - first) / width) as usize;
+        column.min(columns - 1)
+    };
+
+    let mut indices = Vec::with_capacity(columns * 4);
+    let mut start = 0;
+    while start < len {
+        // x values are increasing, so the items of a column are contiguous
+        let column = column_of(start);
+        let mut end = start + 1;
+        while end < len && column_of(end) == column {
+            end += 1;
+        }
+        let (min, max) = extremes(source, start, end);
+        push_sorted(&mut indices, &[start, min, max, end - 1]);
+        start = end;
+    }
+    Some(indices)
+}
+
+/// first index of `bucket` of `len` items split into `buckets` buckets of equal length.
+///
+/// computed in `u64`, since `bucket * len` overflows a 32-bit `usize` for large series.
+#[inline]
+pub(super) fn bucket_start(bucket: usize, len: usize, buckets: usize) -> usize {
+    (bucket as u64 * len as u64 / buckets as u64) as usize
+}
+
+/// indices of the minimum and the maximum y values within `start..end`
+pub(super) fn extremes<S>(source: &S, start: usize, end: usize) -> (usize, usize)
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+{
+    let (mut min, mut max) = (start, start);
+    let (mut min_y, mut max_y) = {
+        let y = source.item_at(start).y();
+        (y, y)
+    };
+    for i in start + 1..end {
+        let y = source.item_at(i).y();
+        if y < min_y {
+            (min, min_y) = (i, y);
+        }
+        if y > max_y {
+            (max, max_y) = (i, y);
+        }
+    }
+    (min, max)
+}
+
+/// appends the indices of a bucket in increasing order, without duplicates
+pub(super) fn push_sorted(indices: &mut Vec<usize>, bucket: &[usize]) {
+    let mut bucket = bucket.to_vec();
+    bucket.sort_unstable();
+    bucket.dedup();
+    indices.extend(bucket);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::lttb::tests::Point;
+
+    /// flat series with an upward and a downward spike close to each other
+    fn spikes() -> Vec<Point> {
+        let mut data: Vec<_> = (0..100).map(|x| Point(x as f64, 0.0)).collect();
+        data[50].1 = 100.0;
+        data[52].1 = -100.0;
+        data
+    }
+
+    fn peaks<'a>(samples: impl Iterator<Item = &'a Point>) -> (f64, f64) {
+        samples.fold((0.0, 0.0), |(min, max), point| {
+            (point.1.min(min), point.1.max(max))
+        })
+    }
+
+    #[test]
+    fn peaks_test() {
+        let data = spikes();
+        // both spikes fall into one bucket of LTTB, which keeps only one of them
+        let lttb = data.as_slice().lttb(12);
+        assert_eq!(lttb.len(), 12);
+        let (min, max) = peaks(lttb);
+        assert!(min > -100.0 || max < 100.0);
+
+        let min_max = data.as_slice().min_max(12);
+        assert!(min_max.len() <= 12);
+        assert_eq!(peaks(min_max), (-100.0, 100.0));
+
+        let m4 = data.as_slice().m4(3);
+        assert!(m4.len() <= 12);
+        assert_eq!(peaks(m4), (-100.0, 100.0));
+    }
+
+    #[test]
+    fn m4_test() {
+        let data = spikes();
+        let samples: Vec<_> = data
+            .as_slice()
+            .m4(2)
+            .map(|point| point.0 as usize)
+            .collect();
+        // first, maximum, minimum and last of both halves
+        assert_eq!(samples, vec![0, 49, 50, 52, 99]);
+
+        // too few points to sample
+        assert_eq!(data.as_slice().m4(25).len(), 100);
+        assert_eq!(data.as_slice().min_max(100).len(), 100);
+    }
+
+    #[test]
+    fn bucket_start_test() {
+        assert_eq!(bucket_start(0, 100, 3), 0);
+        assert_eq!(bucket_start(1, 100, 3), 33);
+        assert_eq!(bucket_start(3, 100, 3), 100);
+        // 50M points in 1000 buckets, whose product exceeds `u32::MAX`
+        assert_eq!(bucket_start(999, 50_000_000, 1000), 49_950_000);
+    }
+}
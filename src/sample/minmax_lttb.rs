@@ -0,0 +1,193 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+//
+
+//! MinMaxLTTB downsampling
+//!
+//! LTTB scans every point of a series, which takes seconds for tens of millions of points.
+//! MinMaxLTTB first keeps the minimum and the maximum of `threshold * ratio / 2` buckets, then
+//! runs LTTB on these candidates only. A `ratio` of 4 keeps the result close to LTTB.
+//!
+//! see: Van Der Donckt et al., "MinMaxLTTB: Leveraging MinMax-Preselection to Scale LTTB", 2023
+//!
+//! ## Known limitations
+//! - X-values must be in a strictly increasing order
+
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::thread;
+
+use super::lttb::{DataPoint, LttbSource};
+use super::minmax::{bucket_start, extremes, push_sorted};
+
+/// items of a source at selected indices, see [`LttbSource::minmax_lttb`]
+pub struct Selected<S: LttbSource> {
+    source: S,
+    /// indices of the selected items, `None` if all items are selected
+    indices: Option<Vec<usize>>,
+}
+
+impl<S: LttbSource> Selected<S> {
+    pub(crate) fn new(source: S, indices: Option<Vec<usize>>) -> Self {
+        Self { source, indices }
+    }
+}
+
+impl<S: LttbSource> LttbSource for Selected<S> {
+    type Item = S::Item;
+
+    #[inline]
+    fn len(&self) -> usize {
+        match &self.indices {
+            Some(indices) => indices.len(),
+            None => self.source.len(),
+        }
+    }
+
+    #[inline]
+    fn item_at(&self, i: usize) -> Self::Item {
+        match &self.indices {
+            Some(indices) => self.source.item_at(indices[i]),
+            None => self.source.item_at(i),
+        }
+    }
+}
+
+/// buckets of the preselection, `None` if LTTB runs on all items
+fn buckets(len: usize, threshold: usize, ratio: usize) -> Option<usize> {
+    let candidates = threshold.saturating_mul(ratio);
+    // the first and the last items are always selected by LTTB
+    if threshold < 3 || ratio < 1 || candidates + 2 >= len {
+        None
+    } else {
+        Some(candidates / 2)
+    }
+}
+
+/// indices of the first and the last items, and of the minimum and the maximum of the inner
+/// buckets
+pub(crate) fn preselect<S>(source: &S, threshold: usize, ratio: usize) -> Option<Vec<usize>>
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+{
+    let len = source.len();
+    let buckets = buckets(len, threshold, ratio)?;
+    let mut indices = Vec::with_capacity(buckets * 2 + 2);
+    indices.push(0);
+    indices.extend(inner(source, len, buckets, 0..buckets));
+    indices.push(len - 1);
+    Some(indices)
+}
+
+/// [`preselect`] in parallel chunks of buckets
+pub(crate) fn par_preselect<S>(source: &S, threshold: usize, ratio: usize) -> Option<Vec<usize>>
+where
+    S: LttbSource + Sync,
+    S::Item: DataPoint,
+{
+    let threads = thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1);
+    chunked(source, threshold, ratio, threads)
+}
+
+/// [`preselect`] in a chunk of buckets per thread
+fn chunked<S>(source: &S, threshold: usize, ratio: usize, threads: usize) -> Option<Vec<usize>>
+where
+    S: LttbSource + Sync,
+    S::Item: DataPoint,
+{
+    let len = source.len();
+    let buckets = buckets(len, threshold, ratio)?;
+    let threads = threads.clamp(1, buckets);
+    // threads cannot be spawned on wasm32
+    if threads == 1 || cfg!(target_arch = "wasm32") {
+        return preselect(source, threshold, ratio);
+    }
+    let chunk = buckets.div_ceil(threads);
+    let chunks: Vec<Vec<usize>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..buckets)
+            .step_by(chunk)
+            .map(|start| {
+                let end = (start + chunk).min(buckets);
+                scope.spawn(move || inner(source, len, buckets, start..end))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("preselection panicked"))
+            .collect()
+    });
+    let mut indices = Vec::with_capacity(buckets * 2 + 2);
+    indices.push(0);
+    indices.extend(chunks.into_iter().flatten());
+    indices.push(len - 1);
+    Some(indices)
+}
+
+/// indices of the minimum and the maximum of the inner buckets within `range`
+fn inner<S>(source: &S, len: usize, buckets: usize, range: Range<usize>) -> Vec<usize>
+where
+    S: LttbSource,
+    S::Item: DataPoint,
+{
+    let mut indices = Vec::with_capacity(range.len() * 2);
+    for bucket in range {
+        let start = 1 + bucket_start(bucket, len - 2, buckets);
+        let end = 1 + bucket_start(bucket + 1, len - 2, buckets);
+        if start < end {
+            let (min, max) = extremes(source, start, end);
+            push_sorted(&mut indices, &[min, max]);
+        }
+    }
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::lttb::tests::{noise, Point};
+
+    /// deterministic noise with a single spike
+    fn spiky(len: usize) -> Vec<Point> {
+        let mut data: Vec<_> = (0..len).map(|i| Point(i as f64, noise(i))).collect();
+        data[len / 3].1 = 1000.0;
+        data
+    }
+
+    #[test]
+    fn minmax_lttb_test() {
+        let data = spiky(10_000);
+        let samples: Vec<_> = data.as_slice().minmax_lttb(100, 4).collect();
+        assert_eq!(samples.len(), 100);
+        assert_eq!(samples[0], &data[0]);
+        assert_eq!(samples[99], &data[9_999]);
+        assert!(samples.iter().any(|point| point.1 == 1000.0));
+
+        // candidates are computed in parallel chunks in the same order
+        let parallel: Vec<_> = data.as_slice().par_minmax_lttb(100, 4).collect();
+        assert_eq!(samples, parallel);
+        assert_eq!(
+            chunked(&data.as_slice(), 100, 4, 3),
+            preselect(&data.as_slice(), 100, 4)
+        );
+        // a single thread runs on the current thread
+        assert_eq!(
+            chunked(&data.as_slice(), 100, 4, 1),
+            preselect(&data.as_slice(), 100, 4)
+        );
+    }
+
+    #[test]
+    fn fallback_test() {
+        // too few points to preselect, the same as LTTB
+        let data = spiky(300);
+        let lttb: Vec<_> = data.as_slice().lttb(100).collect();
+        let minmax_lttb: Vec<_> = data.as_slice().minmax_lttb(100, 4).collect();
+        assert_eq!(lttb, minmax_lttb);
+    }
+}
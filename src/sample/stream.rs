@@ -0,0 +1,182 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+//
+
+//! Streaming LTTB for append-only data
+//!
+//! [`LttbStream`] samples points as they are appended, with buckets of a fixed number of points.
+//! A bucket is finalized once the bucket after it is complete, and its raw points are dropped, so
+//! only the last two buckets are recomputed by [`LttbStream::samples`].
+//!
+//! the samples are the same as those of [`LttbSource::lttb`](super::lttb::LttbSource::lttb)
+//! over all the points, whenever the buckets of both match, i.e. with `n` points and a
+//! threshold of `(n - 2) / bucket_size + 2` if `n - 2` is a multiple of the bucket size.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::sample::stream::LttbStream;
+//!
+//! // a sample every 10 points, at most 1000 samples
+//! let mut stream = LttbStream::new(10).window(1000);
+//! stream.push(point);
+//! chart.draw_series(LineSeries::new(stream.samples().into_iter().map(|p| (p.x, p.y)), &RED))?;
+//! ```
+
+use std::collections::VecDeque;
+
+use super::lttb::{average, select, DataPoint};
+
+/// stateful LTTB sampler of append-only data, see [`crate::sample::stream`]
+#[derive(Debug, Clone)]
+pub struct LttbStream<T> {
+    bucket_size: usize,
+    /// maximum number of finalized samples
+    window: Option<usize>,
+    first: Option<T>,
+    /// samples of finalized buckets
+    selected: VecDeque<T>,
+    /// points after the finalized buckets
+    tail: VecDeque<T>,
+}
+
+impl<T: DataPoint + Clone> LttbStream<T> {
+    /// creates a sampler keeping one point of every `bucket_size` points
+    pub fn new(bucket_size: usize) -> Self {
+        Self {
+            bucket_size: bucket_size.max(1),
+            window: None,
+            first: None,
+            selected: VecDeque::new(),
+            tail: VecDeque::new(),
+        }
+    }
+
+    /// keeps at most `samples` finalized samples, the oldest are dropped first
+    ///
+    /// memory is bounded by `samples` plus two buckets of points.
+    pub fn window(mut self, samples: usize) -> Self {
+        self.window = Some(samples);
+        self.shrink();
+        self
+    }
+
+    /// appends a point, its x value must be greater than the x values of the previous points
+    pub fn push(&mut self, item: T) {
+        if self.first.is_none() {
+            self.first = Some(item);
+            return;
+        }
+        self.tail.push_back(item);
+        let size = self.bucket_size;
+        // the first bucket of the tail is final once the next bucket is complete, and is not the
+        // last point
+        if self.tail.len() > size * 2 {
+            let a = self.selected.back().or(self.first.as_ref()).unwrap();
+            let average = average(self.tail.range(size..size * 2));
+            let b = select(a, self.tail.range(..size), average);
+            let sample = self.tail[b].clone();
+            self.tail.drain(..size);
+            self.selected.push_back(sample);
+            self.shrink();
+        }
+    }
+
+    /// samples of all points appended so far
+    pub fn samples(&self) -> Vec<T> {
+        let mut samples: Vec<T> = self
+            .first
+            .iter()
+            .chain(self.selected.iter())
+            .cloned()
+            .collect();
+        let Some(last) = self.tail.back() else {
+            return samples;
+        };
+        // the last point is kept as is, as by batch LTTB
+        let inner: Vec<&T> = self.tail.range(..self.tail.len() - 1).collect();
+        let buckets: Vec<&[&T]> = inner.chunks(self.bucket_size).collect();
+        for (i, bucket) in buckets.iter().enumerate() {
+            let average = match buckets.get(i + 1) {
+                Some(next) => average(next.iter().copied()),
+                None => (last.x(), last.y()),
+            };
+            let a = samples.last().unwrap();
+            let b = select(a, bucket.iter().copied(), average);
+            samples.push(bucket[b].clone());
+        }
+        samples.push(last.clone());
+        samples
+    }
+
+    /// removes all points
+    pub fn clear(&mut self) {
+        self.first = None;
+        self.selected.clear();
+        self.tail.clear();
+    }
+
+    /// drops the oldest samples beyond the window, the oldest kept sample becomes the first
+    fn shrink(&mut self) {
+        if let Some(window) = self.window {
+            while self.selected.len() > window {
+                self.first = self.selected.pop_front();
+            }
+        }
+    }
+}
+
+impl<T: DataPoint + Clone> Extend<T> for LttbStream<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::lttb::tests::{noise, Point};
+    use crate::sample::lttb::LttbSource;
+
+    fn data(len: usize) -> Vec<Point> {
+        (0..len)
+            .map(|i| Point(i as f64, noise(i) + (i as f64 / 10.0).sin()))
+            .collect()
+    }
+
+    #[test]
+    fn stream_test() {
+        // 20 buckets of 5 points, the first and the last points
+        let data = data(102);
+        let mut stream = LttbStream::new(5);
+        stream.extend(data.iter().copied());
+        let batch: Vec<_> = data.as_slice().lttb(22).copied().collect();
+        assert_eq!(stream.samples(), batch);
+
+        // the tail is sampled as points arrive
+        stream.push(Point(102.0, 0.0));
+        let samples = stream.samples();
+        assert_eq!(samples.len(), 23);
+        assert_eq!(samples.last(), Some(&Point(102.0, 0.0)));
+
+        stream.clear();
+        assert!(stream.samples().is_empty());
+    }
+
+    #[test]
+    fn window_test() {
+        let data = data(10_000);
+        let mut stream = LttbStream::new(10).window(50);
+        stream.extend(data.iter().copied());
+        assert!(stream.selected.len() <= 50);
+        assert!(stream.tail.len() <= 20);
+        let samples = stream.samples();
+        assert!(samples.len() <= 50 + 4);
+        assert_eq!(samples.last(), Some(&data[9_999]));
+        assert!(samples.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
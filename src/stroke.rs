@@ -0,0 +1,201 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Stroke patterns
+//!
+//! plotters styles only carry the color and the width of strokes. Shapes stroked within
+//! [`with_stroke`], e.g. mesh lines or series, are drawn with the dash pattern, line cap and line
+//! join of the given [`StrokeStyle`] by the iced backend.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::stroke::{with_stroke, StrokeStyle};
+//!
+//! with_stroke(StrokeStyle::dashed([6.0, 3.0]), || {
+//!     chart.configure_mesh().draw().expect("failed to draw chart mesh");
+//! });
+//! ```
+
+use std::cell::RefCell;
+
+use iced_widget::canvas::{LineCap, LineDash, LineJoin, Stroke};
+
+/// length of the dashes of dots, dashes of zero length may not be drawn at all
+const DOT_LENGTH: f32 = 0.01;
+
+thread_local! {
+    static CURRENT: RefCell<Option<StrokeStyle>> = const { RefCell::new(None) };
+}
+
+/// extension style of strokes, which is not supported by plotters styles
+#[derive(Debug, Clone, Default)]
+pub struct StrokeStyle {
+    /// alternating lengths of dashes and gaps, solid if empty
+    pub dash: Vec<f32>,
+    /// offset into the dash pattern
+    pub dash_offset: usize,
+    /// shape of the ends of lines
+    pub line_cap: LineCap,
+    /// shape of the corners of lines
+    pub line_join: LineJoin,
+}
+
+impl StrokeStyle {
+    /// create a dashed [`StrokeStyle`] with alternating lengths of dashes and gaps
+    ///
+    /// an odd number of lengths is repeated, e.g. `[4.0, 2.0, 1.0]` is drawn as
+    /// `[4.0, 2.0, 1.0, 4.0, 2.0, 1.0]`.
+    pub fn dashed(dash: impl Into<Vec<f32>>) -> Self {
+        let mut dash = dash.into();
+        // odd patterns would be stroked solid
+        if dash.len() % 2 == 1 {
+            dash.extend_from_within(..);
+        }
+        Self {
+            dash,
+            ..Default::default()
+        }
+    }
+
+    /// create a dotted [`StrokeStyle`] with round dots separated by `gap`
+    pub fn dotted(gap: f32) -> Self {
+        Self {
+            dash: vec![DOT_LENGTH, gap],
+            line_cap: LineCap::Round,
+            ..Default::default()
+        }
+    }
+
+    /// set offset into the dash pattern
+    pub fn dash_offset(mut self, offset: usize) -> Self {
+        self.dash_offset = offset;
+        self
+    }
+
+    /// set line cap
+    pub fn line_cap(mut self, line_cap: LineCap) -> Self {
+        self.line_cap = line_cap;
+        self
+    }
+
+    /// set line join
+    pub fn line_join(mut self, line_join: LineJoin) -> Self {
+        self.line_join = line_join;
+        self
+    }
+
+    /// applies the pattern to an iced [`Stroke`]
+    pub(crate) fn apply<'a>(&'a self, stroke: Stroke<'a>) -> Stroke<'a> {
+        Stroke {
+            line_cap: self.line_cap,
+            line_join: self.line_join,
+            line_dash: LineDash {
+                segments: &self.dash,
+                offset: self.dash_offset,
+            },
+            ..stroke
+        }
+    }
+}
+
+/// strokes shapes drawn by `f` with the [`StrokeStyle`].
+///
+/// Calls can be nested, the innermost [`StrokeStyle`] wins.
+pub fn with_stroke<R>(style: StrokeStyle, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<StrokeStyle>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            CURRENT.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+
+    let previous = CURRENT.with(|current| current.borrow_mut().replace(style));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// calls `f` with the current [`StrokeStyle`]
+pub(crate) fn with_current<R>(f: impl FnOnce(Option<&StrokeStyle>) -> R) -> R {
+    CURRENT.with(|current| f(current.borrow().as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current_dash() -> Option<Vec<f32>> {
+        with_current(|style| style.map(|style| style.dash.clone()))
+    }
+
+    #[test]
+    fn with_stroke_test() {
+        assert_eq!(current_dash(), None);
+        with_stroke(StrokeStyle::dashed([4.0, 2.0]), || {
+            assert_eq!(current_dash(), Some(vec![4.0, 2.0]));
+            with_stroke(StrokeStyle::dotted(3.0), || {
+                assert_eq!(current_dash(), Some(vec![DOT_LENGTH, 3.0]));
+            });
+            assert_eq!(current_dash(), Some(vec![4.0, 2.0]));
+        });
+        assert_eq!(current_dash(), None);
+    }
+
+    /// strokes a horizontal line through the middle of a 20x3 pixmap with the dashes of `style`
+    /// as tiny-skia does, returns the alpha of its pixels
+    fn stroke_line(style: &StrokeStyle) -> Vec<u8> {
+        let mut pixmap = tiny_skia::Pixmap::new(20, 3).unwrap();
+        let stroke = tiny_skia::Stroke {
+            width: 1.0,
+            dash: tiny_skia::StrokeDash::new(style.dash.clone(), 0.0),
+            ..Default::default()
+        };
+        let mut line = tiny_skia::PathBuilder::new();
+        line.move_to(0.0, 1.5);
+        line.line_to(20.0, 1.5);
+        pixmap.stroke_path(
+            &line.finish().unwrap(),
+            &tiny_skia::Paint::default(),
+            &stroke,
+            tiny_skia::Transform::identity(),
+            None,
+        );
+        pixmap.pixels()[20..40].iter().map(|p| p.alpha()).collect()
+    }
+
+    #[test]
+    fn dashed_test() {
+        // tiny-skia strokes odd patterns solid
+        let style = StrokeStyle::dashed([4.0, 2.0, 1.0]);
+        assert_eq!(style.dash, vec![4.0, 2.0, 1.0, 4.0, 2.0, 1.0]);
+        // dashes and gaps alternate
+        let alpha = stroke_line(&style);
+        assert!(alpha[..4].iter().all(|&a| a > 0));
+        assert!(alpha[4..6].iter().all(|&a| a == 0));
+        assert!(alpha[6] > 0);
+        assert!(alpha[7..11].iter().all(|&a| a == 0));
+    }
+
+    #[test]
+    fn dotted_test() {
+        use iced_graphics::Primitive;
+        use iced_widget::{
+            canvas::Path,
+            core::{Point, Size},
+            renderer::wgpu,
+        };
+
+        // wgpu tessellates no dots of zero length
+        let mut frame = wgpu::geometry::Frame::new(Size::new(20.0, 3.0));
+        let line = Path::line(Point::new(0.0, 1.5), Point::new(20.0, 1.5));
+        frame.stroke(&line, StrokeStyle::dotted(5.0).apply(Stroke::default()));
+        let Primitive::Group { primitives } = frame.into_primitive() else {
+            unreachable!()
+        };
+        assert!(!primitives.is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Theme-aware styling
+//!
+//! [`crate::ChartWidget`] maps the iced theme of the application to an [`Appearance`] with
+//! [`StyleSheet`], and passes it to the chart while it is drawn, see [`appearance`]. Caches of the
+//! chart are cleared when the appearance changes, e.g. when the application switches to a dark
+//! theme.
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::style;
+//!
+//! fn build_chart<DB: DrawingBackend>(&self, state: &Self::State, mut builder: ChartBuilder<DB>) {
+//!     let appearance = style::appearance();
+//!     let mut chart = builder.build_cartesian_2d(0.0..100.0, -1.0..1.0).unwrap();
+//!     chart
+//!         .configure_mesh()
+//!         .bold_line_style(appearance.bold_mesh)
+//!         .light_line_style(appearance.light_mesh)
+//!         .axis_style(appearance.axis)
+//!         .label_style(("sans-serif", 15).into_font().color(&appearance.text))
+//!         .draw()
+//!         .unwrap();
+//!     chart.draw_series(LineSeries::new(data, appearance.series_color(0))).unwrap();
+//! }
+//! ```
+
+use std::cell::RefCell;
+
+use iced_widget::{core::Color, style::Theme};
+use plotters::style::RGBAColor;
+
+use crate::utils::scoped;
+
+thread_local! {
+    static APPEARANCE: RefCell<Option<Appearance>> = const { RefCell::new(None) };
+}
+
+/// colors of a chart
+#[derive(Debug, Clone, PartialEq)]
+pub struct Appearance {
+    /// background of the chart
+    pub background: RGBAColor,
+    /// labels and captions
+    pub text: RGBAColor,
+    /// axis lines
+    pub axis: RGBAColor,
+    /// major mesh lines
+    pub bold_mesh: RGBAColor,
+    /// minor mesh lines
+    pub light_mesh: RGBAColor,
+    /// colors of series, see [`Appearance::series_color`]
+    pub series: Vec<RGBAColor>,
+}
+
+impl Appearance {
+    /// color of the series at `index`, repeating the colors of [`Appearance::series`]
+    pub fn series_color(&self, index: usize) -> RGBAColor {
+        if self.series.is_empty() {
+            self.text
+        } else {
+            self.series[index % self.series.len()]
+        }
+    }
+}
+
+impl Default for Appearance {
+    /// black on white, the defaults of plotters
+    fn default() -> Self {
+        let black = |alpha| RGBAColor(0, 0, 0, alpha);
+        Self {
+            background: RGBAColor(255, 255, 255, 1.0),
+            text: black(1.0),
+            axis: black(1.0),
+            bold_mesh: black(0.2),
+            light_mesh: black(0.1),
+            series: vec![
+                RGBAColor(255, 0, 0, 1.0),
+                RGBAColor(0, 0, 255, 1.0),
+                RGBAColor(0, 255, 0, 1.0),
+                RGBAColor(0, 255, 255, 1.0),
+                RGBAColor(255, 0, 255, 1.0),
+                RGBAColor(255, 255, 0, 1.0),
+            ],
+        }
+    }
+}
+
+/// appearance of the chart being drawn, or [`Appearance::default`] outside of drawing
+pub fn appearance() -> Appearance {
+    APPEARANCE.with(|appearance| appearance.borrow().clone().unwrap_or_default())
+}
+
+/// calls `f` with the appearance of the chart
+pub(crate) fn with_appearance<R>(appearance: Appearance, f: impl FnOnce() -> R) -> R {
+    scoped(&APPEARANCE, Some(appearance), f).0
+}
+
+/// maps a theme to the [`Appearance`] of charts
+pub trait StyleSheet {
+    /// style of [`crate::ChartWidget`], see [`crate::ChartWidget::style`]
+    type Style: Default;
+
+    /// appearance of charts of the style
+    fn appearance(&self, style: &Self::Style) -> Appearance;
+}
+
+/// style of charts with the built-in [`Theme`]
+#[derive(Default)]
+pub enum Chart {
+    /// colors of the palette of the theme
+    #[default]
+    Default,
+    /// custom style
+    Custom(Box<dyn StyleSheet<Style = Theme>>),
+}
+
+impl<T: Fn(&Theme) -> Appearance + 'static> From<T> for Chart {
+    fn from(f: T) -> Self {
+        Self::Custom(Box::new(f))
+    }
+}
+
+impl StyleSheet for Theme {
+    type Style = Chart;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        match style {
+            Chart::Default => {
+                let palette = self.extended_palette();
+                let text = palette.background.base.text;
+                Appearance {
+                    background: cvt_color(palette.background.base.color),
+                    text: cvt_color(text),
+                    axis: cvt_color(Color { a: 0.6, ..text }),
+                    bold_mesh: cvt_color(Color { a: 0.2, ..text }),
+                    light_mesh: cvt_color(Color { a: 0.1, ..text }),
+                    series: [
+                        palette.primary.base.color,
+                        palette.success.base.color,
+                        palette.danger.base.color,
+                        palette.secondary.base.color,
+                        palette.primary.strong.color,
+                        palette.success.strong.color,
+                        palette.danger.strong.color,
+                    ]
+                    .into_iter()
+                    .map(cvt_color)
+                    .collect(),
+                }
+            }
+            Chart::Custom(custom) => custom.appearance(self),
+        }
+    }
+}
+
+impl<T: Fn(&Theme) -> Appearance> StyleSheet for T {
+    type Style = Theme;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        (self)(style)
+    }
+}
+
+/// converts an iced color to a plotters color
+fn cvt_color(color: Color) -> RGBAColor {
+    let [r, g, b, _] = color.into_rgba8();
+    RGBAColor(r, g, b, color.a as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appearance_test() {
+        assert_eq!(appearance(), Appearance::default());
+
+        let dark = Theme::Dark.appearance(&Chart::Default);
+        let background = Theme::Dark.palette().background.into_rgba8();
+        assert_eq!(
+            dark.background,
+            RGBAColor(background[0], background[1], background[2], 1.0)
+        );
+        assert_ne!(dark, Theme::Light.appearance(&Chart::Default));
+        assert_eq!(dark.series_color(dark.series.len()), dark.series_color(0));
+
+        let custom = Chart::from(|_: &Theme| Appearance::default());
+        assert_eq!(Theme::Dark.appearance(&custom), Appearance::default());
+
+        with_appearance(dark.clone(), || assert_eq!(appearance(), dark));
+        assert_eq!(appearance(), Appearance::default());
+    }
+}
@@ -0,0 +1,138 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! tooltip of the hovered data point, drawn as an overlay so it can extend beyond the chart
+//!
+//! the tooltip takes the colors of the chart from the theme, see [`crate::style`].
+
+use iced_widget::core::{
+    alignment::{Horizontal, Vertical},
+    layout::{self, Layout},
+    mouse::Cursor,
+    overlay::Overlay,
+    renderer::{Quad, Style},
+    text::{self, LineHeight, Paragraph, Shaping, Text},
+    Border, Color, Point, Size, Vector,
+};
+use plotters::style::Color as _;
+
+use crate::style::StyleSheet;
+use crate::utils::cvt_color;
+
+/// distance between the cursor and the tooltip
+const OFFSET: f32 = 12.0;
+
+/// space around the content
+const PADDING: f32 = 6.0;
+
+pub(crate) struct Tooltip<'a, Style> {
+    /// cursor position, in window coordinates
+    pub position: Point,
+    pub content: &'a str,
+    pub shaping: Shaping,
+    /// style of the chart, mapped to its colors by the theme
+    pub style: &'a Style,
+}
+
+impl<Style> Tooltip<'_, Style> {
+    fn text<Renderer: text::Renderer>(
+        &self,
+        renderer: &Renderer,
+        bounds: Size,
+    ) -> Text<'_, Renderer::Font> {
+        Text {
+            content: self.content,
+            bounds,
+            size: renderer.default_size(),
+            line_height: LineHeight::default(),
+            font: renderer.default_font(),
+            horizontal_alignment: Horizontal::Left,
+            vertical_alignment: Vertical::Top,
+            shaping: self.shaping,
+        }
+    }
+}
+
+impl<Message, Theme, Renderer> Overlay<Message, Theme, Renderer> for Tooltip<'_, Theme::Style>
+where
+    Theme: StyleSheet,
+    Renderer: text::Renderer,
+{
+    fn layout(&mut self, renderer: &Renderer, bounds: Size) -> layout::Node {
+        let paragraph = Renderer::Paragraph::with_text(self.text(renderer, Size::INFINITY));
+        let size = paragraph
+            .min_bounds()
+            .expand(Size::new(PADDING * 2.0, PADDING * 2.0));
+        layout::Node::new(size).move_to(Point::new(
+            place(self.position.x, size.width, bounds.width),
+            place(self.position.y, size.height, bounds.height),
+        ))
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &Style,
+        layout: Layout<'_>,
+        _cursor: Cursor,
+    ) {
+        let appearance = theme.appearance(self.style);
+        let bounds = layout.bounds();
+        renderer.fill_quad(
+            Quad {
+                bounds,
+                border: Border {
+                    color: cvt_color(&appearance.axis.to_backend_color()),
+                    width: 1.0,
+                    radius: 3.0.into(),
+                },
+                ..Default::default()
+            },
+            Color {
+                a: 0.95,
+                ..cvt_color(&appearance.background.to_backend_color())
+            },
+        );
+        let padding = Vector::new(PADDING, PADDING);
+        renderer.fill_text(
+            self.text(renderer, bounds.size()),
+            bounds.position() + padding,
+            cvt_color(&appearance.text.to_backend_color()),
+            bounds,
+        );
+    }
+
+    /// the tooltip never takes events from the chart
+    fn is_over(&self, _layout: Layout<'_>, _renderer: &Renderer, _cursor_position: Point) -> bool {
+        false
+    }
+}
+
+/// start of the tooltip along an axis, at the bottom right of the cursor, flipped at the edges of
+/// the window
+fn place(cursor: f32, length: f32, limit: f32) -> f32 {
+    let start = cursor + OFFSET;
+    if start + length > limit {
+        (cursor - OFFSET - length).max(0.0)
+    } else {
+        start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn place_test() {
+        assert_eq!(place(100.0, 50.0, 500.0), 100.0 + OFFSET);
+        // flipped at the edge
+        assert_eq!(place(480.0, 50.0, 500.0), 480.0 - OFFSET - 50.0);
+        // larger than the window
+        assert_eq!(place(20.0, 600.0, 500.0), 0.0);
+    }
+}
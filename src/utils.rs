@@ -4,26 +4,13 @@
 // Copyright: 2022, Joylei <leingliu@gmail.com>
 // License: MIT
 
+use std::cell::RefCell;
+use std::thread::LocalKey;
+
 use iced_widget::canvas;
 use iced_widget::core::{Color, Point};
 use plotters_backend::{BackendColor, BackendCoord, BackendStyle};
 
-pub(crate) trait AndExt {
-    fn and<F: Fn(Self) -> Self>(self, f: F) -> Self
-    where
-        Self: Sized;
-}
-
-impl<T> AndExt for T {
-    #[inline(always)]
-    fn and<F: Fn(Self) -> Self>(self, f: F) -> Self
-    where
-        Self: Sized,
-    {
-        f(self)
-    }
-}
-
 #[inline]
 pub(crate) fn cvt_color(color: &BackendColor) -> Color {
     let ((r, g, b), a) = (color.rgb, color.alpha);
@@ -31,7 +18,7 @@ pub(crate) fn cvt_color(color: &BackendColor) -> Color {
 }
 
 #[inline]
-pub(crate) fn cvt_stroke<S: BackendStyle>(style: &S) -> canvas::Stroke {
+pub(crate) fn cvt_stroke<S: BackendStyle>(style: &S) -> canvas::Stroke<'static> {
     canvas::Stroke::default()
         .with_color(cvt_color(&style.color()))
         .with_width(style.stroke_width() as f32)
@@ -54,3 +41,36 @@ impl CvtPoint for [f64; 2] {
         Point::new(self[0] as f32, self[1] as f32)
     }
 }
+
+/// calls `f` with `value` in `key`, returns the value taken back afterwards
+pub(crate) fn scoped<T: 'static, R>(
+    key: &'static LocalKey<RefCell<Option<T>>>,
+    value: Option<T>,
+    f: impl FnOnce() -> R,
+) -> (R, Option<T>) {
+    struct Restore<T: 'static> {
+        key: &'static LocalKey<RefCell<Option<T>>>,
+        previous: Option<Option<T>>,
+    }
+
+    impl<T> Restore<T> {
+        fn restore(&mut self) -> Option<T> {
+            let previous = self.previous.take()?;
+            self.key.with(|cell| cell.replace(previous))
+        }
+    }
+
+    impl<T> Drop for Restore<T> {
+        fn drop(&mut self) {
+            self.restore();
+        }
+    }
+
+    let previous = key.with(|cell| cell.replace(value));
+    let mut restore = Restore {
+        key,
+        previous: Some(previous),
+    };
+    let result = f();
+    (result, restore.restore())
+}
@@ -5,38 +5,138 @@
 // License: MIT
 
 use core::marker::PhantomData;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 use iced_widget::{
     canvas::Event,
     core::{
         event,
-        mouse::Cursor,
+        image::FilterMethod,
+        mouse::{self, Cursor, Interaction},
+        overlay,
         renderer::Style,
         widget::{tree, Tree},
-        Element, Layout, Length, Rectangle, Shell, Size, Widget,
+        window, Element, Font, Layout, Length, Point, Rectangle, Shell, Size, Vector, Widget,
     },
     text::Shaping,
 };
+use plotters_backend::{FontFamily, FontStyle};
 
-use crate::renderer::Renderer;
+use crate::backend::Segment;
+use crate::brush::{Brush, BrushMode, Selection};
+use crate::coords::{self, Coords};
+use crate::fonts::{self, FontResolver};
+use crate::gesture::GestureRecognizer;
+use crate::renderer::{DrawOptions, Renderer};
+use crate::style::{Appearance, StyleSheet};
+use crate::tooltip::Tooltip;
+use crate::zoom::{View, ZoomController};
+use crate::Error;
 
 use super::Chart;
 
+/// state of [`ChartWidget`], wraps the state of [`Chart`]
+pub struct WidgetState<S> {
+    pub(crate) chart: S,
+    /// drawing results of the layers of the chart
+    pub(crate) layers: RefCell<Vec<LayerState>>,
+    /// coordinates captured by the last drawing of all layers
+    captured: RefCell<Rc<[Coords]>>,
+    /// error of the last redrawing of the chart, kept while the chart is cached
+    pub(crate) error: RefCell<Option<Error>>,
+    /// error last published with `on_error`
+    published_error: Option<Error>,
+    /// visible ranges of zoomable charts
+    pub(crate) zoom: ZoomController,
+    /// visible ranges of the last drawing of the chart
+    pub(crate) drawn_view: RefCell<Option<View>>,
+    /// appearance of the last drawing of the chart
+    pub(crate) drawn_appearance: RefCell<Option<Appearance>>,
+    /// ongoing box selection
+    pub(crate) brush: Brush,
+    /// cursor position relative to the chart, and the tooltip of the hovered data point
+    tooltip: Option<(Point, String)>,
+    /// fingers pressed within the chart
+    gestures: GestureRecognizer,
+}
+
+impl<S> WidgetState<S> {
+    fn new(chart: S) -> Self {
+        Self {
+            chart,
+            layers: Default::default(),
+            captured: RefCell::new(Rc::new([])),
+            error: Default::default(),
+            published_error: None,
+            zoom: Default::default(),
+            drawn_view: Default::default(),
+            drawn_appearance: Default::default(),
+            brush: Default::default(),
+            tooltip: None,
+            gestures: Default::default(),
+        }
+    }
+
+    /// coordinates captured by the last drawing of all layers
+    pub(crate) fn coords(&self) -> Rc<[Coords]> {
+        self.captured.borrow().clone()
+    }
+
+    /// collects the coordinates captured by the layers, without duplicates of layers drawing the
+    /// same chart
+    pub(crate) fn collect_coords(&self) {
+        let mut coords: Vec<Coords> = Vec::new();
+        for layer in self.layers.borrow().iter() {
+            for captured in layer.coords.borrow().iter() {
+                if !coords.contains(captured) {
+                    coords.push(captured.clone());
+                }
+            }
+        }
+        *self.captured.borrow_mut() = coords.into();
+    }
+}
+
+/// drawing results of a layer of the chart, kept while the layer is cached
+#[derive(Default)]
+pub(crate) struct LayerState {
+    /// geometry and bitmaps of the last drawing of the layer, drawn before its frame
+    pub segments: RefCell<Vec<Segment>>,
+    /// coordinates captured by the last drawing of the layer
+    pub coords: RefCell<Vec<Coords>>,
+}
+
 /// Chart container, turns [`Chart`]s to [`Widget`]s
 pub struct ChartWidget<'a, Message, Theme, Renderer, C>
 where
     C: Chart<Message>,
+    Theme: StyleSheet,
 {
     chart: C,
     width: Length,
     height: Length,
     shaping: Shaping,
+    filter_method: FilterMethod,
+    batching: bool,
+    font_resolver: Box<FontResolver<'a>>,
+    style: Theme::Style,
+    on_error: Option<Box<dyn Fn(Error) -> Message + 'a>>,
+    zoomable: bool,
+    crosshair: bool,
+    brush_mode: BrushMode,
+    on_select: Option<Box<dyn Fn(Selection) -> Message + 'a>>,
+    #[cfg(feature = "png")]
+    on_copy: Option<Box<dyn Fn(crate::export::Image) -> Message + 'a>>,
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    copy_to_clipboard: bool,
     _marker: PhantomData<&'a (Renderer, Theme, Message)>,
 }
 
 impl<'a, Message, Theme, Renderer, C> ChartWidget<'a, Message, Theme, Renderer, C>
 where
     C: Chart<Message> + 'a,
+    Theme: StyleSheet,
 {
     /// create a new [`ChartWidget`]
     pub fn new(chart: C) -> Self {
@@ -45,6 +145,19 @@ where
             width: Length::Fill,
             height: Length::Fill,
             shaping: Default::default(),
+            filter_method: Default::default(),
+            batching: true,
+            font_resolver: Box::new(fonts::resolve_style),
+            style: Default::default(),
+            on_error: None,
+            zoomable: false,
+            crosshair: false,
+            brush_mode: Default::default(),
+            on_select: None,
+            #[cfg(feature = "png")]
+            on_copy: None,
+            #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+            copy_to_clipboard: false,
             _marker: Default::default(),
         }
     }
@@ -66,12 +179,164 @@ where
         self.shaping = shaping;
         self
     }
+
+    /// set filter method of bitmaps, for example heatmaps drawn by `BitMapElement`
+    pub fn image_filter(mut self, filter_method: FilterMethod) -> Self {
+        self.filter_method = filter_method;
+        self
+    }
+
+    /// merge consecutive shapes of the same style into one path, enabled by default
+    #[cfg(feature = "bench")]
+    #[doc(hidden)]
+    pub fn batching(mut self, batching: bool) -> Self {
+        self.batching = batching;
+        self
+    }
+
+    /// set the function mapping plotters font families and styles to iced [`Font`]s,
+    /// defaults to [`fonts::resolve_style`]
+    ///
+    /// ## Example
+    /// ```rust,ignore
+    /// ChartWidget::new(chart).font_resolver(|family, style| match (family.as_str(), style) {
+    ///     ("sans-serif", FontStyle::Italic) => Font::with_name("Noto Sans Italic"),
+    ///     _ => fonts::resolve_style(family, style),
+    /// })
+    /// ```
+    pub fn font_resolver<F>(mut self, f: F) -> Self
+    where
+        F: Fn(FontFamily<'_>, FontStyle) -> Font + 'a,
+    {
+        self.font_resolver = Box::new(f);
+        self
+    }
+
+    /// set the style mapping the theme to the colors passed to the chart, see [`crate::style`]
+    pub fn style(mut self, style: impl Into<Theme::Style>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    /// set the message published when [`Chart::try_draw_chart`] fails
+    ///
+    /// the error is also drawn in place of the chart, and published on the next frame. Charts
+    /// failing on every drawing publish the same error once.
+    pub fn on_error<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Error) -> Message + 'a,
+    {
+        self.on_error = Some(Box::new(f));
+        self
+    }
+
+    /// zoom and pan the chart with the mouse, touches and keyboard, see [`crate::zoom`]
+    ///
+    /// the chart must capture its coordinates, and draw the ranges of [`crate::zoom::visible`].
+    pub fn zoomable(mut self) -> Self {
+        self.zoomable = true;
+        self
+    }
+
+    /// draw a crosshair at the cursor, with the data values under it
+    ///
+    /// the chart must capture its coordinates, see [`crate::coords`].
+    pub fn crosshair(mut self) -> Self {
+        self.crosshair = true;
+        self
+    }
+
+    /// select ranges of data by dragging a rectangle over the chart, see [`crate::brush`]
+    ///
+    /// the message is published once the mouse button is released. Dragging selects instead of
+    /// panning zoomable charts. The chart must capture its coordinates, see [`crate::coords`].
+    pub fn on_select<F>(mut self, mode: BrushMode, f: F) -> Self
+    where
+        F: Fn(Selection) -> Message + 'a,
+    {
+        self.brush_mode = mode;
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    /// rasterize the chart when `Ctrl+C`, or `Cmd+C` on macOS, is pressed over it, and publish
+    /// the message with its image
+    ///
+    /// the image has the size, state, view and appearance of the displayed chart, without
+    /// overlays. See [`ChartWidget::copy_to_clipboard`] to write it to the clipboard as well.
+    #[cfg(feature = "png")]
+    pub fn on_copy<F>(mut self, f: F) -> Self
+    where
+        F: Fn(crate::export::Image) -> Message + 'a,
+    {
+        self.on_copy = Some(Box::new(f));
+        self
+    }
+
+    /// rasterize the chart when `Ctrl+C`, or `Cmd+C` on macOS, is pressed over it, and write its
+    /// image to the clipboard of the system, see [`crate::export::to_clipboard`]
+    ///
+    /// the clipboard of iced only takes text, so the image is written by `arboard`. Failures are
+    /// published with [`ChartWidget::on_error`].
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    pub fn copy_to_clipboard(mut self) -> Self {
+        self.copy_to_clipboard = true;
+        self
+    }
+
+    /// whether the chart is rasterized by the copy shortcut
+    #[cfg(feature = "png")]
+    fn copies(&self) -> bool {
+        #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+        if self.copy_to_clipboard {
+            return true;
+        }
+        self.on_copy.is_some()
+    }
+
+    /// rasterizes the displayed chart, writes it to the clipboard and publishes it
+    #[cfg(feature = "png")]
+    fn copy(
+        &self,
+        state: &WidgetState<C::State>,
+        bounds: Rectangle,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        let result = self.rasterize(state, bounds);
+        #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+        let result = result.and_then(|image| {
+            if self.copy_to_clipboard {
+                crate::export::to_clipboard(&image)?;
+            }
+            Ok(image)
+        });
+        match (result, &self.on_copy, &self.on_error) {
+            (Ok(image), Some(on_copy), _) => shell.publish(on_copy(image)),
+            (Err(error), _, Some(on_error)) => shell.publish(on_error(error)),
+            _ => {}
+        }
+    }
+
+    /// renders the displayed chart to an image
+    #[cfg(feature = "png")]
+    fn rasterize(
+        &self,
+        state: &WidgetState<C::State>,
+        bounds: Rectangle,
+    ) -> Result<crate::export::Image, Error> {
+        let size = (bounds.width as u32, bounds.height as u32);
+        let appearance = state.drawn_appearance.borrow().clone().unwrap_or_default();
+        crate::zoom::with_view(state.zoom.view().cloned(), || {
+            crate::export::to_image(&self.chart, &state.chart, size, &appearance)
+        })
+    }
 }
 
 impl<'a, Message, Theme, Renderer, C> Widget<Message, Theme, Renderer>
     for ChartWidget<'a, Message, Theme, Renderer, C>
 where
     C: Chart<Message>,
+    Theme: StyleSheet,
     Renderer: self::Renderer,
 {
     fn size(&self) -> Size<Length> {
@@ -80,11 +345,11 @@ where
 
     fn tag(&self) -> tree::Tag {
         struct Tag<T>(T);
-        tree::Tag::of::<Tag<C::State>>()
+        tree::Tag::of::<Tag<WidgetState<C::State>>>()
     }
 
     fn state(&self) -> tree::State {
-        tree::State::new(C::State::default())
+        tree::State::new(WidgetState::new(C::State::default()))
     }
 
     #[inline]
@@ -103,14 +368,23 @@ where
         &self,
         tree: &Tree,
         renderer: &mut Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         _style: &Style,
         layout: Layout<'_>,
-        _cursor_position: Cursor,
+        cursor: Cursor,
         _viewport: &Rectangle,
     ) {
-        let state = tree.state.downcast_ref::<C::State>();
-        renderer.draw_chart(state, &self.chart, layout, self.shaping);
+        let state = tree.state.downcast_ref::<WidgetState<C::State>>();
+        let appearance = theme.appearance(&self.style);
+        let options = DrawOptions {
+            shaping: self.shaping,
+            filter_method: self.filter_method,
+            batching: self.batching,
+            font_resolver: &*self.font_resolver,
+            crosshair: self.crosshair,
+            appearance: &appearance,
+        };
+        renderer.draw_chart(state, &self.chart, layout, cursor, &options);
     }
 
     #[inline]
@@ -126,24 +400,111 @@ where
         _rectangle: &Rectangle,
     ) -> event::Status {
         let bounds = layout.bounds();
+        // errors are found while drawing, so they are published with the event following the
+        // drawing, e.g. `RedrawRequested` of the next frame
+        if let Some(on_error) = &self.on_error {
+            // charts failing on every drawing publish their error once, until it changes
+            let state = tree.state.downcast_mut::<WidgetState<C::State>>();
+            let error = state.error.borrow().clone();
+            if error != state.published_error {
+                if let Some(error) = error.clone() {
+                    shell.publish(on_error(error));
+                }
+                state.published_error = error;
+            }
+        }
         let canvas_event = match event {
             iced_widget::core::Event::Mouse(mouse_event) => Some(Event::Mouse(mouse_event)),
             iced_widget::core::Event::Keyboard(keyboard_event) => {
                 Some(Event::Keyboard(keyboard_event))
             }
+            iced_widget::core::Event::Touch(touch_event) => Some(Event::Touch(touch_event)),
             _ => None,
         };
+        let mut status = event::Status::Ignored;
+        // whether a message is published, which updates the chart
+        let mut published = false;
         if let Some(canvas_event) = canvas_event {
-            let state = tree.state.downcast_mut::<C::State>();
+            let state = tree.state.downcast_mut::<WidgetState<C::State>>();
 
-            let (event_status, message) = self.chart.update(state, canvas_event, bounds, cursor);
+            let gestures = match &canvas_event {
+                Event::Touch(touch_event) => state.gestures.update(touch_event, bounds),
+                _ => Vec::new(),
+            };
 
-            if let Some(message) = message {
-                shell.publish(message);
-            }
-            return event_status;
+            let captured = state.coords();
+            status = coords::with_captured(captured.clone(), || {
+                // the chart handles events first, then the brush and the zoom controller take the
+                // ignored ones
+                let (mut event_status, message) =
+                    self.chart
+                        .update(&mut state.chart, canvas_event.clone(), bounds, cursor);
+                if let (Some(on_select), event::Status::Ignored) = (&self.on_select, event_status) {
+                    let (status, selection) = state.brush.update(
+                        self.brush_mode,
+                        &canvas_event,
+                        bounds,
+                        cursor,
+                        &captured,
+                    );
+                    event_status = status;
+                    if let Some(selection) = selection {
+                        shell.publish(on_select(selection));
+                        published = true;
+                    }
+                }
+                if self.zoomable && event_status == event::Status::Ignored {
+                    event_status = state.zoom.update(&canvas_event, bounds, cursor, &captured);
+                }
+
+                if let Some(message) = message {
+                    shell.publish(message);
+                    published = true;
+                }
+
+                #[cfg(feature = "png")]
+                if self.copies()
+                    && event_status == event::Status::Ignored
+                    && is_copy(&canvas_event)
+                    && cursor.is_over(bounds)
+                {
+                    self.copy(state, bounds, shell);
+                    event_status = event::Status::Captured;
+                }
+
+                if let Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft) =
+                    canvas_event
+                {
+                    state.tooltip = cursor.position_in(bounds).and_then(|position| {
+                        let point = captured
+                            .iter()
+                            .find(|coords| coords.plotting_area().contains(position))?
+                            .to_data(position);
+                        let content = self.chart.tooltip(&state.chart, point)?;
+                        Some((position, content))
+                    });
+                }
+
+                for gesture in gestures {
+                    let (mut status, message) =
+                        self.chart.on_gesture(&mut state.chart, gesture, bounds);
+                    if self.zoomable && status == event::Status::Ignored {
+                        status = state.zoom.on_gesture(gesture, bounds, &captured);
+                    }
+                    event_status = event_status.merge(status);
+                    if let Some(message) = message {
+                        shell.publish(message);
+                        published = true;
+                    }
+                }
+                event_status
+            });
         }
-        event::Status::Ignored
+        // handled events change the chart, whose drawing errors are published on the next frame
+        if self.on_error.is_some() && (status == event::Status::Captured || published) {
+            shell.request_redraw(window::RedrawRequest::NextFrame);
+        }
+        status
     }
 
     fn mouse_interaction(
@@ -153,10 +514,54 @@ where
         cursor: Cursor,
         _viewport: &Rectangle,
         _renderer: &Renderer,
-    ) -> iced_widget::core::mouse::Interaction {
-        let state = tree.state.downcast_ref::<C::State>();
+    ) -> Interaction {
+        let state = tree.state.downcast_ref::<WidgetState<C::State>>();
         let bounds = layout.bounds();
-        self.chart.mouse_interaction(state, bounds, cursor)
+        let interaction = coords::with_captured(state.coords(), || {
+            self.chart.mouse_interaction(&state.chart, bounds, cursor)
+        });
+        if interaction != Interaction::Idle {
+            interaction
+        } else if self.on_select.is_some() && state.brush.is_dragging() {
+            Interaction::Crosshair
+        } else if self.zoomable && state.zoom.is_dragging() {
+            Interaction::Grabbing
+        } else {
+            interaction
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let state = tree.state.downcast_ref::<WidgetState<C::State>>();
+        let (position, content) = state.tooltip.as_ref()?;
+        let position = layout.position() + translation + Vector::new(position.x, position.y);
+        Some(overlay::Element::new(Box::new(Tooltip {
+            position,
+            content,
+            shaping: self.shaping,
+            style: &self.style,
+        })))
+    }
+}
+
+/// whether the event is the copy shortcut
+#[cfg(feature = "png")]
+fn is_copy(event: &Event) -> bool {
+    use iced_widget::core::keyboard::{self, Key};
+
+    match event {
+        Event::Keyboard(keyboard::Event::KeyPressed {
+            key: Key::Character(c),
+            modifiers,
+            ..
+        }) => c.as_str() == "c" && modifiers.command(),
+        _ => false,
     }
 }
 
@@ -165,9 +570,204 @@ impl<'a, Message, Theme, Renderer, C> From<ChartWidget<'a, Message, Theme, Rende
 where
     Message: 'a,
     C: Chart<Message> + 'a,
+    Theme: StyleSheet + 'a,
     Renderer: self::Renderer,
 {
     fn from(widget: ChartWidget<'a, Message, Theme, Renderer, C>) -> Self {
         Element::new(widget)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use iced_widget::core::{clipboard, layout, renderer::Style, Pixels, Widget};
+    use plotters::{coord::Shift, drawing::DrawingAreaErrorKind};
+    use plotters_backend::DrawingBackend;
+
+    use super::*;
+    use crate::{ChartBuilder, DrawingArea};
+
+    type Renderer = iced_widget::renderer::Renderer;
+
+    fn renderer() -> Renderer {
+        Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+            iced_tiny_skia::Backend::new(),
+            Font::DEFAULT,
+            Pixels(16.0),
+        ))
+    }
+
+    /// passes the event to the widget of size 200x150, and returns the published messages and
+    /// the requested redraw
+    fn on_event<Message, C: Chart<Message>>(
+        widget: &mut ChartWidget<'_, Message, iced_widget::Theme, Renderer, C>,
+        tree: &mut Tree,
+        event: iced_widget::core::Event,
+    ) -> (Vec<Message>, Option<window::RedrawRequest>) {
+        let bounds = Rectangle::with_size(Size::new(200.0, 150.0));
+        let node = layout::Node::new(bounds.size());
+        let mut messages = Vec::new();
+        let mut shell = Shell::new(&mut messages);
+        widget.on_event(
+            tree,
+            event,
+            Layout::new(&node),
+            Cursor::Available(bounds.center()),
+            &renderer(),
+            &mut clipboard::Null,
+            &mut shell,
+            &bounds,
+        );
+        let redraw = shell.redraw_request();
+        (messages, redraw)
+    }
+
+    /// draws the widget of size 200x150
+    fn draw<Message, C: Chart<Message>>(
+        widget: &ChartWidget<'_, Message, iced_widget::Theme, Renderer, C>,
+        tree: &Tree,
+    ) {
+        let bounds = Rectangle::with_size(Size::new(200.0, 150.0));
+        let node = layout::Node::new(bounds.size());
+        widget.draw(
+            tree,
+            &mut renderer(),
+            &iced_widget::Theme::Light,
+            &Style::default(),
+            Layout::new(&node),
+            Cursor::Unavailable,
+            &bounds,
+        );
+    }
+
+    /// a chart failing on every drawing
+    struct Failing;
+
+    impl Chart<Error> for Failing {
+        type State = ();
+
+        fn build_chart<DB: DrawingBackend>(
+            &self,
+            _state: &Self::State,
+            _builder: ChartBuilder<DB>,
+        ) {
+        }
+
+        fn try_draw_chart<DB: DrawingBackend>(
+            &self,
+            _state: &Self::State,
+            _root: DrawingArea<DB, Shift>,
+        ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+            Err(DrawingAreaErrorKind::LayoutError)
+        }
+    }
+
+    #[test]
+    fn on_error_test() {
+        let mut widget = ChartWidget::new(Failing).on_error(|error| error);
+        let mut tree = Tree::new(&widget as &dyn Widget<_, iced_widget::Theme, Renderer>);
+        let redraw = iced_widget::core::Event::Window(
+            window::Id::MAIN,
+            window::Event::RedrawRequested(std::time::Instant::now()),
+        );
+
+        draw(&widget, &tree);
+        let (errors, _) = on_event(&mut widget, &mut tree, redraw.clone());
+        assert_eq!(errors, vec![Error::Layout]);
+
+        // the chart is not cached, so it fails again with the same error
+        draw(&widget, &tree);
+        assert!(on_event(&mut widget, &mut tree, redraw).0.is_empty());
+
+        // ignored events do not force frames
+        let moved = iced_widget::core::Event::Mouse(mouse::Event::CursorMoved {
+            position: Point::new(100.0, 75.0),
+        });
+        assert_eq!(on_event(&mut widget, &mut tree, moved), (vec![], None));
+    }
+
+    /// a chart with a caption and labeled axes
+    #[cfg(feature = "png")]
+    struct Labels;
+
+    #[cfg(feature = "png")]
+    impl<Message> Chart<Message> for Labels {
+        type State = ();
+
+        fn build_chart<DB: DrawingBackend>(
+            &self,
+            _state: &Self::State,
+            mut builder: ChartBuilder<DB>,
+        ) {
+            let mut chart = builder
+                .caption("Labels", ("sans-serif", 20))
+                .x_label_area_size(20)
+                .y_label_area_size(30)
+                .build_cartesian_2d(0.0..1.0, 0.0..1.0)
+                .unwrap();
+            chart
+                .configure_mesh()
+                .x_desc("x")
+                .y_desc("y")
+                .draw()
+                .unwrap();
+        }
+    }
+
+    /// `Ctrl+C`, or `Cmd+C` on macOS, pressed
+    #[cfg(feature = "png")]
+    fn copy_event() -> iced_widget::core::Event {
+        use iced_widget::core::keyboard::{self, Key, Modifiers};
+
+        iced_widget::core::Event::Keyboard(keyboard::Event::KeyPressed {
+            key: Key::Character("c".into()),
+            location: keyboard::Location::Standard,
+            modifiers: Modifiers::COMMAND,
+            text: None,
+        })
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn copy_test() {
+        use iced_widget::core::keyboard::{self, key, Key, Modifiers};
+
+        let mut widget: ChartWidget<'_, crate::export::Image, iced_widget::Theme, Renderer, _> =
+            ChartWidget::new(Labels).on_copy(|image| image);
+        let mut tree = Tree::new(&widget as &dyn Widget<_, iced_widget::Theme, Renderer>);
+        let (images, _) = on_event(&mut widget, &mut tree, copy_event());
+        assert_eq!(images.len(), 1);
+        let image = &images[0];
+        assert_eq!((image.width, image.height), (200, 150));
+        // the caption is drawn in the top rows, above the plotting area
+        let caption = image.pixels[..200 * 20 * 4]
+            .chunks(4)
+            .filter(|pixel| pixel[0] < 128)
+            .count();
+        assert!(caption > 0, "no caption pixels");
+
+        let other = iced_widget::core::Event::Keyboard(keyboard::Event::KeyPressed {
+            key: Key::Named(key::Named::Enter),
+            location: keyboard::Location::Standard,
+            modifiers: Modifiers::COMMAND,
+            text: None,
+        });
+        assert!(on_event(&mut widget, &mut tree, other).0.is_empty());
+    }
+
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    #[test]
+    #[ignore = "requires the clipboard of a desktop session"]
+    fn clipboard_test() {
+        let mut widget: ChartWidget<'_, Error, iced_widget::Theme, Renderer, _> =
+            ChartWidget::new(Labels)
+                .copy_to_clipboard()
+                .on_error(|error| error);
+        let mut tree = Tree::new(&widget as &dyn Widget<_, iced_widget::Theme, Renderer>);
+        let (errors, _) = on_event(&mut widget, &mut tree, copy_event());
+        assert_eq!(errors, vec![]);
+
+        let image = arboard::Clipboard::new().unwrap().get_image().unwrap();
+        assert_eq!((image.width, image.height), (200, 150));
+    }
+}
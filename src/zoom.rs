@@ -0,0 +1,355 @@
+// plotters-iced
+//
+// Iced backend for Plotters
+// Copyright: 2022, Joylei <leingliu@gmail.com>
+// License: MIT
+
+//! Zoom and pan
+//!
+//! [`crate::ChartWidget::zoomable`] keeps the visible ranges of a chart in the widget state, and
+//! changes them with the mouse wheel, dragging, pinch and pan gestures and keyboard shortcuts,
+//! double click resets them. The chart must capture its coordinates, see [`crate::coords`], and
+//! draw the ranges returned by [`visible`].
+//!
+//! | input | action |
+//! |-------|--------|
+//! | mouse wheel, pinch | zoom around the cursor |
+//! | drag, two-finger pan | pan |
+//! | double click, `0` | reset |
+//! | `+`, `-` | zoom around the center |
+//! | arrow keys | pan |
+//!
+//! ## Example
+//! ```rust,ignore
+//! use plotters_iced::{coords::CaptureCoords, zoom};
+//!
+//! fn build_chart<DB: DrawingBackend>(&self, state: &Self::State, mut builder: ChartBuilder<DB>) {
+//!     let (x, y) = zoom::visible(0.0..100.0, -1.0..1.0);
+//!     let mut chart = builder.build_cartesian_2d(x, y).unwrap();
+//!     chart.capture_coords();
+//!     //...
+//! }
+//!
+//! ChartWidget::new(chart).zoomable()
+//! ```
+
+use std::cell::RefCell;
+use std::ops::Range;
+
+use iced_widget::{
+    canvas::Event,
+    core::{
+        event::Status,
+        keyboard::{self, key::Named, Key},
+        mouse::{self, click, Click, Cursor, ScrollDelta},
+        Point, Rectangle, Vector,
+    },
+};
+
+use crate::coords::Coords;
+use crate::gesture::Gesture;
+use crate::utils::scoped;
+
+thread_local! {
+    static VIEW: RefCell<Option<View>> = const { RefCell::new(None) };
+}
+
+/// zoom factor of one line of the mouse wheel, or of a key press
+const ZOOM_STEP: f32 = 0.9;
+
+/// pixels scrolled by touchpads per line
+const PIXELS_PER_LINE: f32 = 50.0;
+
+/// pixels panned by a key press
+const PAN_STEP: f32 = 20.0;
+
+/// visible ranges of a zoomed or panned chart
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    /// visible range of the x axis
+    pub x: Range<f64>,
+    /// visible range of the y axis
+    pub y: Range<f64>,
+}
+
+/// view of the chart being drawn, `None` if the chart is not zoomed nor panned
+pub fn view() -> Option<View> {
+    VIEW.with(|view| view.borrow().clone())
+}
+
+/// visible ranges of the chart being drawn, or the full ranges if it is not zoomed nor panned
+pub fn visible(x: Range<f64>, y: Range<f64>) -> (Range<f64>, Range<f64>) {
+    match view() {
+        Some(view) => (view.x, view.y),
+        None => (x, y),
+    }
+}
+
+/// calls `f` with the view of the chart
+pub(crate) fn with_view<R>(view: Option<View>, f: impl FnOnce() -> R) -> R {
+    scoped(&VIEW, view, f).0
+}
+
+/// keeps the view of a zoomable chart
+#[derive(Debug, Default)]
+pub(crate) struct ZoomController {
+    view: Option<View>,
+    /// cursor position and coordinates when dragging started
+    drag: Option<(Point, Coords)>,
+    last_click: Option<Click>,
+}
+
+impl ZoomController {
+    /// the current view
+    pub fn view(&self) -> Option<&View> {
+        self.view.as_ref()
+    }
+
+    /// whether the chart is being dragged
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// changes the view on user input
+    pub fn update(
+        &mut self,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+        coords: &[Coords],
+    ) -> Status {
+        let position = cursor.position_in(bounds);
+        let target = position.and_then(|p| coords.iter().find(|c| c.plotting_area().contains(p)));
+        match event {
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let (Some(position), Some(coords)) = (position, target) else {
+                    return Status::Ignored;
+                };
+                let lines = match *delta {
+                    ScrollDelta::Lines { y, .. } => y,
+                    ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE,
+                };
+                self.zoom(&self.current(coords), position, ZOOM_STEP.powf(lines))
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let (Some(position), Some(coords)) = (position, target) else {
+                    return Status::Ignored;
+                };
+                let click = Click::new(position, self.last_click);
+                self.last_click = Some(click);
+                if matches!(click.kind(), click::Kind::Double) {
+                    self.drag = None;
+                    return self.reset();
+                }
+                self.drag = Some((position, self.current(coords)));
+                Status::Captured
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let (Some((origin, coords)), Some(position)) =
+                    (&self.drag, cursor.position_from(bounds.position()))
+                else {
+                    return Status::Ignored;
+                };
+                let delta = position - *origin;
+                self.set_view(View {
+                    x: coords.x().panned(delta.x),
+                    y: coords.y().panned(delta.y),
+                })
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                match self.drag.take() {
+                    Some(_) => Status::Captured,
+                    None => Status::Ignored,
+                }
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => {
+                // keys are handled only while the cursor is over the chart
+                let (Some(_), Some(coords)) = (position, target.or(coords.first())) else {
+                    return Status::Ignored;
+                };
+                let coords = &self.current(coords);
+                let center = coords.plotting_area().center();
+                match key.as_ref() {
+                    Key::Character("+" | "=") => self.zoom(coords, center, ZOOM_STEP),
+                    Key::Character("-") => self.zoom(coords, center, 1.0 / ZOOM_STEP),
+                    Key::Character("0") => self.reset(),
+                    Key::Named(Named::ArrowLeft) => self.pan(coords, Vector::new(PAN_STEP, 0.0)),
+                    Key::Named(Named::ArrowRight) => self.pan(coords, Vector::new(-PAN_STEP, 0.0)),
+                    Key::Named(Named::ArrowUp) => self.pan(coords, Vector::new(0.0, PAN_STEP)),
+                    Key::Named(Named::ArrowDown) => self.pan(coords, Vector::new(0.0, -PAN_STEP)),
+                    _ => Status::Ignored,
+                }
+            }
+            _ => Status::Ignored,
+        }
+    }
+
+    /// changes the view on gestures
+    ///
+    /// gestures made by the same event, e.g. a pinch and a pan, are applied one after another.
+    pub fn on_gesture(&mut self, gesture: Gesture, bounds: Rectangle, coords: &[Coords]) -> Status {
+        let (Gesture::Pinch { center, .. } | Gesture::Pan { center, .. }) = gesture;
+        let center = center - Vector::new(bounds.x, bounds.y);
+        let Some(coords) = coords.iter().find(|c| c.plotting_area().contains(center)) else {
+            return Status::Ignored;
+        };
+        let coords = &self.current(coords);
+        match gesture {
+            Gesture::Pinch { scale, .. } if scale > 0.0 => self.zoom(coords, center, 1.0 / scale),
+            Gesture::Pinch { .. } => Status::Ignored,
+            Gesture::Pan { delta, .. } => self.pan(coords, delta),
+        }
+    }
+
+    /// coordinates of the current view, which may have changed since the last drawing, so that
+    /// changes between two drawings add up
+    fn current(&self, coords: &Coords) -> Coords {
+        match &self.view {
+            Some(view) => coords.with_ranges(&view.x, &view.y),
+            None => coords.clone(),
+        }
+    }
+
+    fn zoom(&mut self, coords: &Coords, center: Point, factor: f32) -> Status {
+        self.set_view(View {
+            x: coords.x().zoomed(center.x, factor),
+            y: coords.y().zoomed(center.y, factor),
+        })
+    }
+
+    fn pan(&mut self, coords: &Coords, delta: Vector) -> Status {
+        self.set_view(View {
+            x: coords.x().panned(delta.x),
+            y: coords.y().panned(delta.y),
+        })
+    }
+
+    /// shows the whole chart again, nothing is captured if it is not zoomed
+    fn reset(&mut self) -> Status {
+        match self.view.take() {
+            Some(_) => Status::Captured,
+            None => Status::Ignored,
+        }
+    }
+
+    /// sets the view, unless its ranges are too small or not finite
+    fn set_view(&mut self, view: View) -> Status {
+        if is_valid(&view.x) && is_valid(&view.y) {
+            self.view = Some(view);
+        }
+        Status::Captured
+    }
+}
+
+/// checks that the range can be mapped to pixels with `f64` precision
+fn is_valid(range: &Range<f64>) -> bool {
+    let (start, end) = (range.start, range.end);
+    let magnitude = start.abs().max(end.abs()).max(f64::MIN_POSITIVE);
+    start.is_finite() && end.is_finite() && (end - start).abs() > magnitude * 1e-12
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::tests::capture_ranges;
+
+    #[test]
+    fn zoom_test() {
+        let bounds = Rectangle::new(Point::new(10.0, 10.0), [100.0, 100.0].into());
+        let coords = capture_ranges(0.0..10.0, 0.0..10.0);
+        let mut zoom = ZoomController::default();
+
+        // out of the chart
+        let wheel = Event::Mouse(mouse::Event::WheelScrolled {
+            delta: ScrollDelta::Lines { x: 0.0, y: 1.0 },
+        });
+        let status = zoom.update(&wheel, bounds, Cursor::Unavailable, &coords);
+        assert_eq!(status, Status::Ignored);
+        assert!(zoom.view().is_none());
+
+        let cursor = Cursor::Available(Point::new(60.0, 60.0));
+        assert_eq!(
+            zoom.update(&wheel, bounds, cursor, &coords),
+            Status::Captured
+        );
+        let view = zoom.view().unwrap();
+        assert!((view.x.start - 0.5).abs() < 1e-6 && (view.x.end - 9.5).abs() < 1e-6);
+        assert!((view.y.start - 0.5).abs() < 1e-6 && (view.y.end - 9.5).abs() < 1e-6);
+
+        assert_eq!(zoom.reset(), Status::Captured);
+        assert!(zoom.view().is_none());
+        assert_eq!(zoom.reset(), Status::Ignored);
+
+        // ranges can not collapse
+        zoom.zoom(&coords[0], Point::new(50.0, 50.0), 0.0);
+        assert!(zoom.view().is_none());
+    }
+
+    #[test]
+    fn drag_test() {
+        let bounds = Rectangle::new(Point::ORIGIN, [100.0, 100.0].into());
+        let coords = capture_ranges(0.0..10.0, 0.0..10.0);
+        let mut zoom = ZoomController::default();
+
+        let press = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+        zoom.update(
+            &press,
+            bounds,
+            Cursor::Available(Point::new(50.0, 50.0)),
+            &coords,
+        );
+        let position = Point::new(60.0, 40.0);
+        let moved = Event::Mouse(mouse::Event::CursorMoved { position });
+        zoom.update(&moved, bounds, Cursor::Available(position), &coords);
+        let release = Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left));
+        zoom.update(&release, bounds, Cursor::Available(position), &coords);
+
+        // the content follows the cursor
+        let view = zoom.view().unwrap();
+        assert!((view.x.start + 1.0).abs() < 1e-6 && (view.x.end - 9.0).abs() < 1e-6);
+        assert!((view.y.start + 1.0).abs() < 1e-6 && (view.y.end - 9.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gestures_test() {
+        let bounds = Rectangle::new(Point::ORIGIN, [100.0, 100.0].into());
+        let coords = capture_ranges(0.0..10.0, 0.0..10.0);
+        let mut zoom = ZoomController::default();
+
+        // a two-finger move before the chart is drawn again zooms and pans
+        let pinch = Gesture::Pinch {
+            center: Point::new(50.0, 50.0),
+            scale: 2.0,
+        };
+        let pan = Gesture::Pan {
+            center: Point::new(55.0, 50.0),
+            delta: Vector::new(10.0, 0.0),
+        };
+        zoom.on_gesture(pinch, bounds, &coords);
+        zoom.on_gesture(pan, bounds, &coords);
+        let view = zoom.view().unwrap();
+        assert!((view.x.start - 2.0).abs() < 1e-6 && (view.x.end - 7.0).abs() < 1e-6);
+        assert!((view.y.start - 2.5).abs() < 1e-6 && (view.y.end - 7.5).abs() < 1e-6);
+
+        // gestures outside of the plotting area are ignored
+        let pinch = Gesture::Pinch {
+            center: Point::new(150.0, 50.0),
+            scale: 2.0,
+        };
+        let mut zoom = ZoomController::default();
+        assert_eq!(zoom.on_gesture(pinch, bounds, &coords), Status::Ignored);
+        assert!(zoom.view().is_none());
+
+        // wheel events between two drawings add up
+        let mut zoom = ZoomController::default();
+        let wheel = Event::Mouse(mouse::Event::WheelScrolled {
+            delta: ScrollDelta::Lines { x: 0.0, y: 1.0 },
+        });
+        let cursor = Cursor::Available(Point::new(50.0, 50.0));
+        zoom.update(&wheel, bounds, cursor, &coords);
+        zoom.update(&wheel, bounds, cursor, &coords);
+        let view = zoom.view().unwrap();
+        let width = 10.0 * (ZOOM_STEP as f64).powi(2);
+        assert!((view.x.end - view.x.start - width).abs() < 1e-4);
+    }
+}